@@ -0,0 +1,681 @@
+use crate::{
+    cell::State,
+    rule::Rule,
+    topology::Bounds,
+    universe::{CartesianPoint, Universe, universe_iterate},
+};
+use manfredo::cartesian::rect::rect_i32;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatternClass {
+    Extinct,
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Puffer,
+    Gun,
+    Methuselah,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cycle {
+    pub class: PatternClass,
+    pub period: u64,
+    pub start: u64,
+    pub displacement: CartesianPoint,
+    pub phase: u64,
+    pub min_population: usize,
+    pub max_population: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Classification {
+    pub class: PatternClass,
+    pub period: Option<u64>,
+    pub speed: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassifySettings {
+    pub front_depth: i32,
+    pub home_margin: i32,
+    pub emission_periods: usize,
+    pub methuselah_lifespan: u64,
+    pub settle_window: usize,
+    pub settle_max_period: usize,
+}
+
+impl Default for ClassifySettings {
+    fn default() -> Self {
+        ClassifySettings {
+            front_depth: 64,
+            home_margin: 8,
+            emission_periods: 3,
+            methuselah_lifespan: 100,
+            settle_window: 100,
+            settle_max_period: 50,
+        }
+    }
+}
+
+const DIRECTIONS: [(i32, i32); 8] =
+    [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+
+fn cell_hash(point: &CartesianPoint, state: &State) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    point.hash(&mut hasher);
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn background_hash(universe: &Universe) -> u64 {
+    if universe.background == State::Dead {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    universe.background.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, PartialEq)]
+struct Shape {
+    value: HashMap<CartesianPoint, State>,
+    background: State,
+}
+
+fn universe_hash(universe: &Universe) -> (u64, CartesianPoint, Shape) {
+    let (hash, corner, value) = normalized_hash(universe.value.iter());
+    let shape = Shape { value, background: universe.background };
+    (hash.wrapping_add(background_hash(universe)), corner, shape)
+}
+
+fn corner<'a>(points: impl Iterator<Item = &'a CartesianPoint>) -> CartesianPoint {
+    let (x, y) =
+        points.fold((i32::MAX, i32::MAX), |(x, y), point| (x.min(point.x), y.min(point.y)));
+    if x == i32::MAX { CartesianPoint::of(0, 0) } else { CartesianPoint::of(x, y) }
+}
+
+fn normalized_hash<'a>(
+    cells: impl Iterator<Item = (&'a CartesianPoint, &'a State)> + Clone,
+) -> (u64, CartesianPoint, HashMap<CartesianPoint, State>) {
+    let corner = corner(cells.clone().map(|(point, _)| point));
+    let value: HashMap<CartesianPoint, State> = cells
+        .map(|(point, state)| (CartesianPoint::of(point.x - corner.x, point.y - corner.y), *state))
+        .collect();
+    let hash =
+        value.iter().fold(0, |acc: u64, (point, state)| acc.wrapping_add(cell_hash(point, state)));
+    (hash, corner, value)
+}
+
+pub fn analysis_hash(universe: &Universe) -> u64 {
+    universe.value.iter().fold(background_hash(universe), |acc: u64, (point, state)| {
+        acc.wrapping_add(cell_hash(point, state))
+    })
+}
+
+pub fn analysis_normalized_hash(universe: &Universe) -> u64 {
+    universe_hash(universe).0
+}
+
+struct Tracker {
+    origin: u64,
+    seen: HashMap<u64, Vec<(u64, CartesianPoint, Shape)>>,
+    hashes: Vec<u64>,
+    populations: Vec<usize>,
+}
+
+impl Tracker {
+    fn new(origin: u64) -> Self {
+        Tracker { origin, seen: HashMap::new(), hashes: Vec::new(), populations: Vec::new() }
+    }
+
+    fn track(
+        &mut self,
+        first_age: u64,
+        age: u64,
+        hash: u64,
+        corner: CartesianPoint,
+        shape: Shape,
+    ) -> Option<Cycle> {
+        let population = shape.value.len();
+        let seen = self.seen.entry(hash).or_default();
+        if let Some((start, start_corner, _)) = seen.iter().find(|(_, _, seen)| *seen == shape) {
+            let period = age - start;
+            let from = (start - self.origin) as usize;
+            let populations = &self.populations[from..];
+            let min_population = populations.iter().copied().min().unwrap_or(0);
+            let max_population = populations.iter().copied().max().unwrap_or(0);
+            let displacement =
+                CartesianPoint::of(corner.x - start_corner.x, corner.y - start_corner.y);
+            let canonical = start
+                + self.hashes[from..]
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, hash)| **hash)
+                    .map(|(i, _)| i as u64)
+                    .unwrap_or(0);
+            let phase = (i128::from(first_age) - i128::from(canonical))
+                .rem_euclid(i128::from(period)) as u64;
+            let class = match (max_population, period, displacement.x != 0 || displacement.y != 0) {
+                (0, _, _) => PatternClass::Extinct,
+                (_, _, true) => PatternClass::Spaceship,
+                (_, 1, false) => PatternClass::StillLife,
+                _ => PatternClass::Oscillator,
+            };
+            return Some(Cycle {
+                class,
+                period,
+                start: *start,
+                displacement,
+                phase,
+                min_population,
+                max_population,
+            });
+        }
+        seen.push((age, corner, shape));
+        self.hashes.push(hash);
+        self.populations.push(population);
+        None
+    }
+}
+
+pub fn analysis_find_cycle(
+    universe: &Universe,
+    rule: &Rule,
+    max_generations: u64,
+) -> Option<Cycle> {
+    let mut universe = universe.clone();
+    let mut tracker = Tracker::new(universe.age);
+    let first_age = universe.age;
+    for _ in 0..=max_generations {
+        let (hash, corner, shape) = universe_hash(&universe);
+        if let Some(cycle) = tracker.track(first_age, universe.age, hash, corner, shape) {
+            return Some(cycle);
+        }
+        universe_iterate(&mut universe, rule);
+    }
+    None
+}
+
+struct FrontTracker {
+    depth: i32,
+    trackers: Vec<Tracker>,
+}
+
+impl FrontTracker {
+    fn new(origin: u64, depth: i32) -> Self {
+        FrontTracker { depth, trackers: DIRECTIONS.iter().map(|_| Tracker::new(origin)).collect() }
+    }
+
+    fn track(&mut self, first_age: u64, universe: &Universe) -> Option<Cycle> {
+        for (direction, tracker) in DIRECTIONS.iter().zip(self.trackers.iter_mut()) {
+            let projection = |point: &CartesianPoint| point.x * direction.0 + point.y * direction.1;
+            let Some(edge) = universe.value.keys().map(projection).max() else {
+                continue;
+            };
+            let front =
+                universe.value.iter().filter(|(point, _)| projection(point) >= edge - self.depth);
+            let (hash, corner, value) = normalized_hash(front);
+            let shape = Shape { value, background: State::Dead };
+            if let Some(cycle) = tracker.track(first_age, universe.age, hash, corner, shape) {
+                let advance =
+                    cycle.displacement.x * direction.0 + cycle.displacement.y * direction.1;
+                if cycle.class == PatternClass::Spaceship && advance > 0 {
+                    return Some(Cycle { class: PatternClass::Puffer, ..cycle });
+                }
+                *tracker = Tracker::new(universe.age);
+            }
+        }
+        None
+    }
+}
+
+pub fn analysis_find_front_cycle(
+    universe: &Universe,
+    rule: &Rule,
+    max_generations: u64,
+    depth: i32,
+) -> Option<Cycle> {
+    let mut universe = universe.clone();
+    let first_age = universe.age;
+    let mut front = FrontTracker::new(universe.age, depth);
+    for _ in 0..=max_generations {
+        if let Some(cycle) = front.track(first_age, &universe) {
+            return Some(cycle);
+        }
+        universe_iterate(&mut universe, rule);
+    }
+    None
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn speed_fraction(distance: u64, period: u64) -> String {
+    match (distance, period) {
+        (1, 1) => "c".into(),
+        (distance, 1) => format!("{distance}c"),
+        (1, period) => format!("c/{period}"),
+        (distance, period) => format!("{distance}c/{period}"),
+    }
+}
+
+pub fn analysis_speed(cycle: &Cycle) -> Option<String> {
+    let x = u64::from(cycle.displacement.x.unsigned_abs());
+    let y = u64::from(cycle.displacement.y.unsigned_abs());
+    if x == 0 && y == 0 {
+        return None;
+    }
+    if x == 0 || y == 0 || x == y {
+        let distance = x.max(y);
+        let divisor = gcd(distance, cycle.period);
+        let direction = if x == y { "diagonal" } else { "orthogonal" };
+        return Some(format!(
+            "{} {direction}",
+            speed_fraction(distance / divisor, cycle.period / divisor)
+        ));
+    }
+    let (a, b) = (x.max(y), x.min(y));
+    let divisor = gcd(gcd(a, b), cycle.period);
+    let period = cycle.period / divisor;
+    let (a, b) = (a / divisor, b / divisor);
+    Some(if period == 1 {
+        format!("({a},{b})c oblique")
+    } else {
+        format!("({a},{b})c/{period} oblique")
+    })
+}
+
+fn home_bounds(universe: &Universe, margin: i32) -> Bounds {
+    let min = corner(universe.value.keys());
+    let (x, y) = universe
+        .value
+        .keys()
+        .fold((min.x, min.y), |(x, y), point| (x.max(point.x), y.max(point.y)));
+    Bounds::of(min.x - margin, min.y - margin, x + margin, y + margin)
+}
+
+fn inside(universe: &Universe, bounds: &Bounds) -> Universe {
+    Universe {
+        value: universe
+            .value
+            .iter()
+            .filter(|(point, _)| rect_i32::contains_point(bounds, point))
+            .map(|(point, state)| (point.clone(), *state))
+            .collect(),
+        age: universe.age,
+        ..Default::default()
+    }
+}
+
+fn emits(home: &Universe, bounds: &Bounds, rule: &Rule, period: u64, periods: usize) -> bool {
+    let mut universe = home.clone();
+    let mut outside = 0;
+    for _ in 0..periods {
+        for _ in 0..period {
+            universe_iterate(&mut universe, rule);
+        }
+        let inner = inside(&universe, bounds);
+        let count = universe.value.len() - inner.value.len();
+        if inner.value != home.value || count <= outside {
+            return false;
+        }
+        outside = count;
+    }
+    true
+}
+
+fn settled_lifespan(populations: &[usize], settings: &ClassifySettings) -> Option<u64> {
+    let len = populations.len();
+    if len < settings.settle_window {
+        return None;
+    }
+    (1..=settings.settle_max_period).find_map(|period| {
+        let mut i = len - 1;
+        while i >= period && populations[i] == populations[i - period] {
+            i -= 1;
+        }
+        let start = if i < period { 0 } else { i + 1 - period };
+        if len - start >= settings.settle_window { Some(start as u64) } else { None }
+    })
+}
+
+fn classify_cycle(cycle: &Cycle, first_age: u64, lifespan: u64) -> Classification {
+    if cycle.class != PatternClass::Spaceship && cycle.start - first_age >= lifespan {
+        return Classification { class: PatternClass::Methuselah, period: None, speed: None };
+    }
+    Classification {
+        class: cycle.class.clone(),
+        period: if cycle.class == PatternClass::Extinct { None } else { Some(cycle.period) },
+        speed: analysis_speed(cycle),
+    }
+}
+
+pub fn analysis_classify(
+    universe: &Universe,
+    rule: &Rule,
+    max_generations: u64,
+    settings: &ClassifySettings,
+) -> Option<Classification> {
+    let mut universe = universe.clone();
+    let first_age = universe.age;
+    let bounds = home_bounds(&universe, settings.home_margin);
+    let mut whole = Tracker::new(first_age);
+    let mut home = Tracker::new(first_age);
+    let mut front = FrontTracker::new(first_age, settings.front_depth);
+    let mut home_cycle: Option<(Cycle, Universe)> = None;
+    let mut front_cycle: Option<Cycle> = None;
+    let mut populations = Vec::new();
+    for _ in 0..=max_generations {
+        let (hash, corner, shape) = universe_hash(&universe);
+        if let Some(cycle) = whole.track(first_age, universe.age, hash, corner, shape) {
+            return Some(classify_cycle(&cycle, first_age, settings.methuselah_lifespan));
+        }
+        if home_cycle.is_none() {
+            let inner = inside(&universe, &bounds);
+            let hash = analysis_hash(&inner);
+            let origin = CartesianPoint::of(0, 0);
+            let shape = Shape { value: inner.value.clone(), background: State::Dead };
+            if let Some(cycle) = home.track(first_age, universe.age, hash, origin, shape) {
+                home_cycle = Some((cycle, inner));
+            }
+        }
+        if front_cycle.is_none() {
+            front_cycle = front.track(first_age, &universe);
+        }
+        populations.push(universe.value.len());
+        universe_iterate(&mut universe, rule);
+    }
+    if let Some((cycle, inner)) = home_cycle
+        && emits(&inner, &bounds, rule, cycle.period, settings.emission_periods)
+    {
+        return Some(Classification {
+            class: PatternClass::Gun,
+            period: Some(cycle.period),
+            speed: front_cycle.as_ref().and_then(analysis_speed),
+        });
+    }
+    if settled_lifespan(&populations, settings)
+        .is_some_and(|lifespan| lifespan >= settings.methuselah_lifespan)
+    {
+        return Some(Classification { class: PatternClass::Methuselah, period: None, speed: None });
+    }
+    front_cycle.map(|cycle| Classification {
+        class: PatternClass::Puffer,
+        period: Some(cycle.period),
+        speed: analysis_speed(&cycle),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Classification, ClassifySettings, Cycle, PatternClass, Shape, Tracker, analysis_classify,
+        analysis_find_cycle, analysis_find_front_cycle, analysis_hash, analysis_normalized_hash,
+        analysis_speed,
+    };
+    use crate::{
+        cell::State,
+        preset::get_preset,
+        rule::{Rule, rule_from_str},
+        universe::{CartesianPoint, Universe, universe_from_str, universe_iterate},
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_analysis_hash() {
+        let a = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(1, 0)]);
+        let b = Universe::from([CartesianPoint::of(1, 0), CartesianPoint::of(0, 0)]);
+        let c = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(0, 1)]);
+        assert_eq!(analysis_hash(&a), analysis_hash(&b));
+        assert_ne!(analysis_hash(&a), analysis_hash(&c));
+        assert_eq!(analysis_hash(&Universe::default()), 0);
+    }
+
+    #[test]
+    fn analysis_still_life() {
+        assert_eq!(
+            analysis_find_cycle(&get_preset("block"), &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::StillLife,
+                period: 1,
+                start: 0,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 0,
+                min_population: 4,
+                max_population: 4
+            })
+        );
+        assert_eq!(
+            analysis_find_cycle(&get_preset("boat"), &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::StillLife,
+                period: 1,
+                start: 0,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 0,
+                min_population: 6,
+                max_population: 6
+            })
+        );
+    }
+
+    #[test]
+    fn analysis_oscillator() {
+        assert_eq!(
+            analysis_find_cycle(&get_preset("blinker"), &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::Oscillator,
+                period: 2,
+                start: 0,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 1,
+                min_population: 3,
+                max_population: 3
+            })
+        );
+        let universe = universe_from_str([
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬜⬜⬛",
+            "⬛⬜⬜⬜⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+        ]);
+        assert_eq!(
+            analysis_find_cycle(&universe, &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::Oscillator,
+                period: 2,
+                start: 0,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 0,
+                min_population: 6,
+                max_population: 6
+            })
+        );
+    }
+
+    #[test]
+    fn analysis_settles() {
+        let universe = universe_from_str(["⬛⬛⬛⬛", "⬛⬜⬜⬛", "⬛⬜⬛⬛", "⬛⬛⬛⬛"]);
+        assert_eq!(
+            analysis_find_cycle(&universe, &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::StillLife,
+                period: 1,
+                start: 1,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 0,
+                min_population: 4,
+                max_population: 4
+            })
+        );
+        let universe = universe_from_str(["⬜⬛⬛", "⬛⬛⬛", "⬛⬛⬜"]);
+        assert_eq!(
+            analysis_find_cycle(&universe, &Rule::default(), 10),
+            Some(Cycle {
+                class: PatternClass::Extinct,
+                period: 1,
+                start: 1,
+                displacement: CartesianPoint::of(0, 0),
+                phase: 0,
+                min_population: 0,
+                max_population: 0
+            })
+        );
+        let mut universe = get_preset("blinker");
+        universe.age = 10;
+        assert_eq!(analysis_find_cycle(&universe, &Rule::default(), 10).map(|c| c.start), Some(10));
+    }
+
+    #[test]
+    fn analysis_hash_collision() {
+        let shape = |x| Shape {
+            value: HashMap::from([(CartesianPoint::of(x, 0), State::Alive)]),
+            background: State::Dead,
+        };
+        let origin = CartesianPoint::of(0, 0);
+        let mut tracker = Tracker::new(0);
+        assert_eq!(tracker.track(0, 0, 7, origin.clone(), shape(0)), None);
+        assert_eq!(tracker.track(0, 1, 7, origin.clone(), shape(1)), None);
+        let cycle = tracker.track(0, 2, 7, origin, shape(1)).unwrap();
+        assert_eq!((cycle.class, cycle.period, cycle.start), (PatternClass::StillLife, 1, 1));
+    }
+
+    #[test]
+    fn analysis_other_rules() {
+        let cycle = analysis_find_cycle(&get_preset("blinker"), &rule_from_str("B3/S"), 20);
+        assert_eq!(cycle.map(|c| (c.class, c.start)), Some((PatternClass::Extinct, 2)));
+    }
+
+    #[test]
+    fn analysis_no_cycle() {
+        assert_eq!(analysis_find_cycle(&get_preset("r_pentomino"), &Rule::default(), 100), None);
+    }
+
+    #[test]
+    fn test_analysis_normalized_hash() {
+        let a = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(1, 0)]);
+        let b = Universe::from([CartesianPoint::of(5, -3), CartesianPoint::of(6, -3)]);
+        let c = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(0, 1)]);
+        assert_eq!(analysis_normalized_hash(&a), analysis_normalized_hash(&b));
+        assert_ne!(analysis_normalized_hash(&a), analysis_normalized_hash(&c));
+    }
+
+    #[test]
+    fn analysis_glider() {
+        let cycle = analysis_find_cycle(&get_preset("glider"), &Rule::default(), 10).unwrap();
+        assert_eq!(cycle.class, PatternClass::Spaceship);
+        assert_eq!(cycle.period, 4);
+        assert_eq!(cycle.start, 0);
+        assert_eq!(cycle.displacement.x.abs(), 1);
+        assert_eq!(cycle.displacement.y.abs(), 1);
+        assert_eq!((cycle.min_population, cycle.max_population), (5, 5));
+        assert_eq!(analysis_speed(&cycle), Some("c/4 diagonal".into()));
+    }
+
+    #[test]
+    fn analysis_phase() {
+        let mut universe = get_preset("glider");
+        let phase = analysis_find_cycle(&universe, &Rule::default(), 10).unwrap().phase;
+        for i in 1..8 {
+            universe_iterate(&mut universe, &Rule::default());
+            let cycle = analysis_find_cycle(&universe, &Rule::default(), 10).unwrap();
+            assert_eq!(cycle.phase, (phase + i) % 4);
+        }
+    }
+
+    #[test]
+    fn analysis_lightweight_spaceship() {
+        let universe = universe_from_str([
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬜⬛⬛⬜⬛⬛",
+            "⬛⬛⬛⬛⬛⬜⬛",
+            "⬛⬜⬛⬛⬛⬜⬛",
+            "⬛⬛⬜⬜⬜⬜⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+        ]);
+        let cycle = analysis_find_cycle(&universe, &Rule::default(), 10).unwrap();
+        assert_eq!(cycle.class, PatternClass::Spaceship);
+        assert_eq!(cycle.period, 4);
+        assert_eq!(cycle.displacement, CartesianPoint::of(2, 0));
+        assert_eq!((cycle.min_population, cycle.max_population), (9, 12));
+        assert_eq!(analysis_speed(&cycle), Some("c/2 orthogonal".into()));
+    }
+
+    #[test]
+    fn analysis_puffer() {
+        let universe = get_preset("puffer1");
+        assert_eq!(analysis_find_cycle(&universe, &Rule::default(), 300), None);
+        let cycle = analysis_find_front_cycle(&universe, &Rule::default(), 300, 64).unwrap();
+        assert_eq!(cycle.class, PatternClass::Puffer);
+        assert_eq!(cycle.period, 128);
+        assert_eq!(cycle.displacement, CartesianPoint::of(0, 64));
+        assert_eq!(analysis_speed(&cycle), Some("c/2 orthogonal".into()));
+    }
+
+    #[test]
+    fn test_analysis_speed() {
+        let cycle = |x, y, period| Cycle {
+            class: PatternClass::Spaceship,
+            period,
+            start: 0,
+            displacement: CartesianPoint::of(x, y),
+            phase: 0,
+            min_population: 1,
+            max_population: 1,
+        };
+        assert_eq!(analysis_speed(&cycle(0, 0, 2)), None);
+        assert_eq!(analysis_speed(&cycle(1, -1, 4)), Some("c/4 diagonal".into()));
+        assert_eq!(analysis_speed(&cycle(-2, 0, 4)), Some("c/2 orthogonal".into()));
+        assert_eq!(analysis_speed(&cycle(0, 2, 5)), Some("2c/5 orthogonal".into()));
+        assert_eq!(analysis_speed(&cycle(3, 3, 12)), Some("c/4 diagonal".into()));
+        assert_eq!(analysis_speed(&cycle(1, 0, 1)), Some("c orthogonal".into()));
+        assert_eq!(analysis_speed(&cycle(2, 1, 6)), Some("(2,1)c/6 oblique".into()));
+        assert_eq!(analysis_speed(&cycle(-2, 4, 10)), Some("(2,1)c/5 oblique".into()));
+    }
+
+    #[test]
+    fn test_analysis_classify() {
+        let universe = universe_from_str(["⬜⬛⬛", "⬛⬛⬛", "⬛⬛⬜"]);
+        assert_eq!(
+            analysis_classify(&universe, &Rule::default(), 10, &ClassifySettings::default()),
+            Some(Classification { class: PatternClass::Extinct, period: None, speed: None })
+        );
+        assert_eq!(
+            analysis_classify(
+                &get_preset("blinker"),
+                &Rule::default(),
+                10,
+                &ClassifySettings::default()
+            ),
+            Some(Classification { class: PatternClass::Oscillator, period: Some(2), speed: None })
+        );
+        assert_eq!(
+            analysis_classify(
+                &get_preset("r_pentomino"),
+                &Rule::default(),
+                100,
+                &ClassifySettings::default()
+            ),
+            None
+        );
+        let tromino = universe_from_str(["⬜⬜", "⬜⬛"]);
+        assert_eq!(
+            analysis_classify(&tromino, &Rule::default(), 10, &ClassifySettings::default()),
+            Some(Classification { class: PatternClass::StillLife, period: Some(1), speed: None })
+        );
+        let settings = ClassifySettings { methuselah_lifespan: 1, ..Default::default() };
+        assert_eq!(
+            analysis_classify(&tromino, &Rule::default(), 10, &settings),
+            Some(Classification { class: PatternClass::Methuselah, period: None, speed: None })
+        );
+    }
+}
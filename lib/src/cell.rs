@@ -1,20 +1,97 @@
-#[derive(Debug, PartialEq, Clone, Copy)]
+use crate::{
+    neighbor::NeighborConfiguration,
+    rule::{Rule, rule_count_matches},
+    table::{Table, table_next_state},
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
 pub enum State {
+    #[default]
     Dead,
     Alive,
+    Dying(u8),
+    Other(u8),
+}
+
+fn cell_decay(rule: &Rule, index: u8) -> State {
+    if index + 1 < rule.generations { State::Dying(index + 1) } else { State::Dead }
+}
+
+pub fn cell_iterate(rule: &Rule, state: State, number_of_alive_neighbors: u32) -> State {
+    match state {
+        State::Alive => {
+            if rule_count_matches(&rule.survival, number_of_alive_neighbors) {
+                State::Alive
+            } else {
+                cell_decay(rule, 1)
+            }
+        }
+        State::Dead => {
+            if rule_count_matches(&rule.birth, number_of_alive_neighbors) {
+                State::Alive
+            } else {
+                State::Dead
+            }
+        }
+        State::Dying(index) => cell_decay(rule, index),
+        State::Other(_) => State::Dead,
+    }
 }
 
-pub fn cell_iterate(state: State, number_of_alive_neighbors: u8) -> State {
+pub fn cell_iterate_configuration(
+    rule: &Rule,
+    state: State,
+    configuration: NeighborConfiguration,
+) -> State {
+    let Some(isotropic) = &rule.isotropic else {
+        return cell_iterate(rule, state, configuration.count_ones());
+    };
     match state {
-        State::Alive => match number_of_alive_neighbors {
-            2 => State::Alive,
-            3 => State::Alive,
-            _ => State::Dead,
-        },
-        State::Dead => match number_of_alive_neighbors {
-            3 => State::Alive,
-            _ => State::Dead,
-        },
+        State::Alive => {
+            if isotropic.survival.binary_search(&configuration).is_ok() {
+                State::Alive
+            } else {
+                cell_decay(rule, 1)
+            }
+        }
+        State::Dead => {
+            if isotropic.birth.binary_search(&configuration).is_ok() {
+                State::Alive
+            } else {
+                State::Dead
+            }
+        }
+        State::Dying(index) => cell_decay(rule, index),
+        State::Other(_) => State::Dead,
+    }
+}
+
+pub fn cell_iterate_table(table: &Table, state: State, neighbors: &[State]) -> State {
+    let neighbors: Vec<u8> = neighbors.iter().map(cell_index).collect();
+    cell_of_table_index(table_next_state(table, cell_index(&state), &neighbors))
+}
+
+pub fn cell_index(state: &State) -> u8 {
+    match state {
+        State::Dead => 0,
+        State::Alive => 1,
+        State::Dying(index) | State::Other(index) => *index,
+    }
+}
+
+pub fn cell_of_index(index: u8) -> State {
+    match index {
+        0 => State::Dead,
+        1 => State::Alive,
+        index => State::Dying(index),
+    }
+}
+
+pub fn cell_of_table_index(index: u8) -> State {
+    match index {
+        0 => State::Dead,
+        1 => State::Alive,
+        index => State::Other(index),
     }
 }
 
@@ -22,6 +99,7 @@ pub fn cell_toggle(state: &State) -> State {
     match state {
         State::Alive => State::Dead,
         State::Dead => State::Alive,
+        State::Dying(_) | State::Other(_) => State::Dead,
     }
 }
 
@@ -39,46 +117,162 @@ pub fn cell_of(c: char) -> State {
 
 #[cfg(test)]
 mod tests {
-    use super::{State, cell_iterate, cell_of, cell_toggle, cell_try_of};
+    use super::{
+        State, cell_index, cell_iterate, cell_iterate_configuration, cell_iterate_table, cell_of,
+        cell_of_index, cell_of_table_index, cell_toggle, cell_try_of,
+    };
+    use crate::{
+        rule::{Rule, rule_from_str},
+        table::table_from_str,
+    };
 
     #[test]
     fn any_live_cell_with_fewer_than_two_live_neighbours_dies() {
-        assert_eq!(cell_iterate(State::Alive, 0), State::Dead);
-        assert_eq!(cell_iterate(State::Alive, 1), State::Dead);
+        let rule = Rule::default();
+        assert_eq!(cell_iterate(&rule, State::Alive, 0), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 1), State::Dead);
     }
 
     #[test]
     fn any_live_cell_with_two_or_three_live_neighbours_lives() {
-        assert_eq!(cell_iterate(State::Alive, 2), State::Alive);
-        assert_eq!(cell_iterate(State::Alive, 3), State::Alive);
+        let rule = Rule::default();
+        assert_eq!(cell_iterate(&rule, State::Alive, 2), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Alive, 3), State::Alive);
     }
 
     #[test]
     fn any_live_cell_with_more_than_three_live_neighbours_dies() {
-        assert_eq!(cell_iterate(State::Alive, 4), State::Dead);
-        assert_eq!(cell_iterate(State::Alive, 5), State::Dead);
-        assert_eq!(cell_iterate(State::Alive, 6), State::Dead);
-        assert_eq!(cell_iterate(State::Alive, 7), State::Dead);
-        assert_eq!(cell_iterate(State::Alive, 8), State::Dead);
+        let rule = Rule::default();
+        assert_eq!(cell_iterate(&rule, State::Alive, 4), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 5), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 6), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 7), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 8), State::Dead);
     }
 
     #[test]
     fn any_dead_cell_with_exactly_three_live_neighbours_becomes_a_live_cell() {
-        assert_eq!(cell_iterate(State::Dead, 0), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 1), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 2), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 3), State::Alive);
-        assert_eq!(cell_iterate(State::Dead, 4), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 5), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 6), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 7), State::Dead);
-        assert_eq!(cell_iterate(State::Dead, 8), State::Dead);
+        let rule = Rule::default();
+        assert_eq!(cell_iterate(&rule, State::Dead, 0), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 1), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 2), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 3), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Dead, 4), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 5), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 6), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 7), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 8), State::Dead);
+    }
+
+    #[test]
+    fn cell_iterate_high_life() {
+        let rule = rule_from_str("B36/S23");
+        assert_eq!(cell_iterate(&rule, State::Dead, 3), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Dead, 6), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Alive, 6), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 2), State::Alive);
+    }
+
+    #[test]
+    fn cell_iterate_seeds() {
+        let rule = rule_from_str("B2/S");
+        assert_eq!(cell_iterate(&rule, State::Dead, 2), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Alive, 2), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 3), State::Dead);
+    }
+
+    #[test]
+    fn cell_iterate_brians_brain() {
+        let rule = rule_from_str("B2/S/C3");
+        assert_eq!(cell_iterate(&rule, State::Dead, 2), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Dead, 3), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 2), State::Dying(2));
+        assert_eq!(cell_iterate(&rule, State::Alive, 3), State::Dying(2));
+        assert_eq!(cell_iterate(&rule, State::Dying(2), 2), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Other(2), 2), State::Dead);
+    }
+
+    #[test]
+    fn cell_iterate_star_wars() {
+        let rule = rule_from_str("345/2/4");
+        assert_eq!(cell_iterate(&rule, State::Alive, 3), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Alive, 2), State::Dying(2));
+        assert_eq!(cell_iterate(&rule, State::Dying(2), 3), State::Dying(3));
+        assert_eq!(cell_iterate(&rule, State::Dying(3), 3), State::Dead);
+    }
+
+    #[test]
+    fn cell_iterate_larger_than_life() {
+        let rule = rule_from_str("R5,C0,M1,S34..58,B34..45,NM");
+        assert_eq!(cell_iterate(&rule, State::Dead, 33), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Dead, 34), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Dead, 46), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 58), State::Alive);
+        assert_eq!(cell_iterate(&rule, State::Alive, 59), State::Dead);
+        assert_eq!(cell_iterate(&rule, State::Alive, 120), State::Dead);
+    }
+
+    #[test]
+    fn test_cell_iterate_configuration() {
+        let rule = rule_from_str("B2-a/S12");
+        assert_eq!(cell_iterate_configuration(&rule, State::Dead, 0b00000101), State::Alive);
+        assert_eq!(cell_iterate_configuration(&rule, State::Dead, 0b00000011), State::Dead);
+        assert_eq!(cell_iterate_configuration(&rule, State::Dead, 0b00000111), State::Dead);
+        assert_eq!(cell_iterate_configuration(&rule, State::Alive, 0b00000011), State::Alive);
+        assert_eq!(cell_iterate_configuration(&rule, State::Alive, 0b00000111), State::Dead);
+        let rule = Rule::default();
+        assert_eq!(cell_iterate_configuration(&rule, State::Dead, 0b10000011), State::Alive);
+        assert_eq!(cell_iterate_configuration(&rule, State::Alive, 0b00000001), State::Dead);
+    }
+
+    #[test]
+    fn test_cell_index() {
+        assert_eq!(cell_index(&State::Dead), 0);
+        assert_eq!(cell_index(&State::Alive), 1);
+        assert_eq!(cell_index(&State::Dying(2)), 2);
+        assert_eq!(cell_index(&State::Other(3)), 3);
+        for index in 0..=255 {
+            assert_eq!(cell_index(&cell_of_index(index)), index);
+            assert_eq!(cell_index(&cell_of_table_index(index)), index);
+        }
+    }
+
+    #[test]
+    fn test_cell_of_table_index() {
+        assert_eq!(cell_of_table_index(0), State::Dead);
+        assert_eq!(cell_of_table_index(1), State::Alive);
+        assert_eq!(cell_of_table_index(2), State::Other(2));
+    }
+
+    #[test]
+    fn test_cell_iterate_table() {
+        let table = table_from_str(
+            "@RULE Test\n@TABLE\nn_states:3\nneighborhood:vonNeumann\nsymmetries:none\n0,1,0,0,0,2\n",
+        );
+        assert_eq!(
+            cell_iterate_table(
+                &table,
+                State::Dead,
+                &[State::Alive, State::Dead, State::Dead, State::Dead]
+            ),
+            State::Other(2)
+        );
+        assert_eq!(
+            cell_iterate_table(
+                &table,
+                State::Other(2),
+                &[State::Dead, State::Dead, State::Dead, State::Dead]
+            ),
+            State::Other(2)
+        );
     }
 
     #[test]
     fn test_cell_toggle() {
         assert_eq!(cell_toggle(&State::Alive), State::Dead);
         assert_eq!(cell_toggle(&State::Dead), State::Alive);
+        assert_eq!(cell_toggle(&State::Dying(2)), State::Dead);
+        assert_eq!(cell_toggle(&State::Other(2)), State::Dead);
     }
 
     #[test]
@@ -0,0 +1,252 @@
+use crate::{
+    analysis::analysis_find_cycle,
+    cell::State,
+    preset::{get_preset, get_preset_groups},
+    rule::Rule,
+    transform::{TRANSFORMS, transform_point},
+    universe::{CartesianPoint, Universe, universe_iterate},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+};
+
+const CENSUS_MAX_PERIOD: u64 = 64;
+
+const PRESET_KEYS_CAPACITY: usize = 8;
+
+type CanonicalKey = Vec<(i32, i32, State)>;
+
+type PresetKeys = Arc<HashMap<CanonicalKey, String>>;
+
+static PRESET_KEYS: LazyLock<Mutex<Vec<(Rule, PresetKeys)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CensusEntry {
+    pub id: Option<String>,
+    pub object: Universe,
+    pub count: usize,
+}
+
+fn canonical_key(universe: &Universe) -> CanonicalKey {
+    TRANSFORMS
+        .iter()
+        .map(|transform| {
+            let cells: Vec<(i32, i32, State)> = universe
+                .value
+                .iter()
+                .map(|(point, state)| {
+                    let point = transform_point(*transform, point);
+                    (point.x, point.y, *state)
+                })
+                .collect();
+            let min_x = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+            let min_y = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+            let mut key: CanonicalKey =
+                cells.into_iter().map(|(x, y, state)| (x - min_x, y - min_y, state)).collect();
+            key.sort_unstable();
+            key
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn key_universe(key: &CanonicalKey) -> Universe {
+    Universe::from(
+        key.iter()
+            .map(|(x, y, state)| (CartesianPoint::of(*x, *y), *state))
+            .collect::<HashMap<CartesianPoint, State>>(),
+    )
+}
+
+fn object_key(object: &Universe, rule: &Rule) -> CanonicalKey {
+    let Some(cycle) = analysis_find_cycle(object, rule, CENSUS_MAX_PERIOD)
+        .filter(|cycle| cycle.start == object.age)
+    else {
+        return canonical_key(object);
+    };
+    let mut universe = object.clone();
+    let mut key = canonical_key(&universe);
+    for _ in 1..cycle.period {
+        universe_iterate(&mut universe, rule);
+        key = key.min(canonical_key(&universe));
+    }
+    key
+}
+
+fn preset_keys_build(rule: &Rule) -> HashMap<CanonicalKey, String> {
+    let mut keys = HashMap::new();
+    for group in get_preset_groups() {
+        for sub_group in group.sub_groups {
+            for item in sub_group.items {
+                let preset = get_preset(&item.id);
+                if census_components(&preset, 1).len() == 1 {
+                    keys.entry(object_key(&preset, rule)).or_insert(item.id);
+                }
+            }
+        }
+    }
+    keys
+}
+
+fn preset_keys(rule: &Rule) -> PresetKeys {
+    let mut cache = PRESET_KEYS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, keys)) = cache.iter().find(|(cached, _)| cached == rule) {
+        return Arc::clone(keys);
+    }
+    if cache.len() >= PRESET_KEYS_CAPACITY {
+        cache.remove(0);
+    }
+    let keys = Arc::new(preset_keys_build(rule));
+    cache.push((rule.clone(), Arc::clone(&keys)));
+    keys
+}
+
+pub fn census_components(universe: &Universe, distance: u32) -> Vec<Universe> {
+    let distance = distance as i32;
+    let mut visited: HashSet<CartesianPoint> = HashSet::new();
+    let mut points: Vec<&CartesianPoint> = universe.value.keys().collect();
+    points.sort_unstable_by_key(|point| (point.y, point.x));
+    let mut components = Vec::new();
+    for point in points {
+        if !visited.insert(point.clone()) {
+            continue;
+        }
+        let mut value = HashMap::new();
+        let mut queue = VecDeque::from([point.clone()]);
+        while let Some(current) = queue.pop_front() {
+            for dy in -distance..=distance {
+                for dx in -distance..=distance {
+                    let neighbor = CartesianPoint::of(current.x + dx, current.y + dy);
+                    if universe.value.contains_key(&neighbor) && visited.insert(neighbor.clone()) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            value.insert(current.clone(), universe.value[&current]);
+        }
+        components.push(Universe { value, age: universe.age, ..Default::default() });
+    }
+    components
+}
+
+pub fn census_canonical(universe: &Universe) -> Universe {
+    key_universe(&canonical_key(universe))
+}
+
+pub fn census(universe: &Universe, rule: &Rule, distance: u32) -> Vec<CensusEntry> {
+    let presets = preset_keys(rule);
+    let mut counts: HashMap<CanonicalKey, usize> = HashMap::new();
+    for object in census_components(universe, distance) {
+        *counts.entry(object_key(&object, rule)).or_insert(0) += 1;
+    }
+    let mut entries: Vec<CensusEntry> = counts
+        .into_iter()
+        .map(|(key, count)| CensusEntry {
+            id: presets.get(&key).cloned(),
+            object: key_universe(&key),
+            count,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| census_entry_name(a).cmp(&census_entry_name(b)))
+            .then_with(|| a.object.value.len().cmp(&b.object.value.len()))
+    });
+    entries
+}
+
+pub fn census_entry_name(entry: &CensusEntry) -> String {
+    match &entry.id {
+        Some(id) => id.clone(),
+        None => format!("{}-cell object", entry.object.value.len()),
+    }
+}
+
+pub fn census_to_string(entries: &[CensusEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| format!("{} {}", entry.count, census_entry_name(entry)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{census, census_canonical, census_components, census_to_string, preset_keys};
+    use crate::{
+        preset::get_preset,
+        rule::{Rule, rule_from_str},
+        universe::{CartesianPoint, Universe, universe_from_str, universe_iterate},
+    };
+    use std::sync::Arc;
+
+    fn place(target: &mut Universe, source: &Universe, x: i32, y: i32) {
+        for (point, state) in source.value.iter() {
+            target.value.insert(CartesianPoint::of(point.x + x, point.y + y), *state);
+        }
+    }
+
+    #[test]
+    fn test_preset_keys() {
+        let keys = preset_keys(&Rule::default());
+        assert!(Arc::ptr_eq(&keys, &preset_keys(&Rule::default())));
+        assert!(!Arc::ptr_eq(&keys, &preset_keys(&rule_from_str("B36/S23"))));
+        assert!(keys.values().any(|id| id == "glider"));
+    }
+
+    #[test]
+    fn test_census_components() {
+        let universe = Universe::from([
+            CartesianPoint::of(0, 0),
+            CartesianPoint::of(1, 1),
+            CartesianPoint::of(3, 1),
+            CartesianPoint::of(10, 10),
+        ]);
+        assert_eq!(census_components(&Universe::default(), 1), vec![]);
+        assert_eq!(census_components(&universe, 1).len(), 3);
+        assert_eq!(census_components(&universe, 2).len(), 2);
+        assert_eq!(census_components(&universe, 9).len(), 1);
+        let sizes: Vec<usize> =
+            census_components(&universe, 1).iter().map(|c| c.value.len()).collect();
+        assert_eq!(sizes, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn test_census_canonical() {
+        let l = universe_from_str(["⬜⬛⬛", "⬜⬛⬛", "⬜⬜⬛"]);
+        let rotated = universe_from_str(["⬛⬛⬛", "⬜⬜⬜", "⬜⬛⬛"]);
+        let mirrored = universe_from_str(["⬛⬛⬜", "⬛⬛⬜", "⬛⬜⬜"]);
+        let other = universe_from_str(["⬜⬜⬜", "⬛⬛⬛", "⬛⬛⬛"]);
+        let canonical = census_canonical(&l);
+        assert_eq!(census_canonical(&rotated), canonical);
+        assert_eq!(census_canonical(&mirrored), canonical);
+        assert_ne!(census_canonical(&other), canonical);
+        assert_eq!(canonical.value.len(), 4);
+    }
+
+    #[test]
+    fn test_census() {
+        let mut glider = get_preset("glider");
+        universe_iterate(&mut glider, &Rule::default());
+        let mut blinker = get_preset("blinker");
+        universe_iterate(&mut blinker, &Rule::default());
+        let mut universe = Universe::default();
+        place(&mut universe, &get_preset("block"), 0, 0);
+        place(&mut universe, &get_preset("block"), 20, 0);
+        place(&mut universe, &get_preset("block"), 0, 20);
+        place(&mut universe, &blinker, 20, 20);
+        place(&mut universe, &glider, 40, 40);
+        place(&mut universe, &get_preset("boat"), -20, 0);
+        place(&mut universe, &Universe::from([CartesianPoint::of(-50, -50)]), 0, 0);
+        let entries = census(&universe, &Rule::default(), 1);
+        assert_eq!(
+            census_to_string(&entries),
+            "3 block, 1 1-cell object, 1 blinker, 1 boat, 1 glider"
+        );
+        assert_eq!(entries[0].object, census_canonical(&get_preset("block")));
+        assert_eq!(census_to_string(&census(&Universe::default(), &Rule::default(), 1)), "");
+    }
+}
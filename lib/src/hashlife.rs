@@ -0,0 +1,694 @@
+use crate::{
+    cell::State,
+    rule::{Rule, rule_count_matches, rule_is_life_like},
+    topology::Topology,
+    universe::{CartesianPoint, Universe},
+};
+use std::{collections::HashMap, fmt};
+
+const DEFAULT_NODE_LIMIT: usize = 1 << 22;
+const MAX_LEVEL: u8 = 32;
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedRuleErr;
+
+impl fmt::Display for UnsupportedRuleErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HashLife supports only two-state rules on the Moore neighborhood of radius 1")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedTopologyErr;
+
+impl fmt::Display for UnsupportedTopologyErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HashLife supports only the unbounded plane")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StepTooLargeErr;
+
+impl fmt::Display for StepTooLargeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HashLife supports only steps of up to 2^{} generations", MAX_LEVEL - 3)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PatternTooLargeErr;
+
+impl fmt::Display for PatternTooLargeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The pattern grew too large for HashLife")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HashLifeErr {
+    UnsupportedRule(UnsupportedRuleErr),
+    UnsupportedTopology(UnsupportedTopologyErr),
+    StepTooLarge(StepTooLargeErr),
+    PatternTooLarge(PatternTooLargeErr),
+}
+
+impl fmt::Display for HashLifeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashLifeErr::UnsupportedRule(err) => err.fmt(f),
+            HashLifeErr::UnsupportedTopology(err) => err.fmt(f),
+            HashLifeErr::StepTooLarge(err) => err.fmt(f),
+            HashLifeErr::PatternTooLarge(err) => err.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    nw: u32,
+    ne: u32,
+    sw: u32,
+    se: u32,
+    population: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<(u32, u32, u32, u32), u32>,
+    results: HashMap<(u32, u8), u32>,
+    empty: Vec<u32>,
+    root: u32,
+    pinned: Vec<u32>,
+    node_limit: usize,
+    collect_at: usize,
+    collections: u64,
+    pub age: u64,
+}
+
+const DEAD: u32 = 0;
+const ALIVE: u32 = 1;
+
+fn leaf(population: u64) -> Node {
+    Node { level: 0, nw: 0, ne: 0, sw: 0, se: 0, population }
+}
+
+fn join(hashlife: &mut HashLife, nw: u32, ne: u32, sw: u32, se: u32) -> u32 {
+    if let Some(id) = hashlife.index.get(&(nw, ne, sw, se)) {
+        return *id;
+    }
+    let nodes = &hashlife.nodes;
+    let node = Node {
+        level: nodes[nw as usize].level + 1,
+        nw,
+        ne,
+        sw,
+        se,
+        population: nodes[nw as usize].population
+            + nodes[ne as usize].population
+            + nodes[sw as usize].population
+            + nodes[se as usize].population,
+    };
+    let id = hashlife.nodes.len() as u32;
+    hashlife.nodes.push(node);
+    hashlife.index.insert((nw, ne, sw, se), id);
+    id
+}
+
+fn empty(hashlife: &mut HashLife, level: u8) -> u32 {
+    while hashlife.empty.len() <= usize::from(level) {
+        let e = *hashlife.empty.last().unwrap();
+        let id = join(hashlife, e, e, e, e);
+        hashlife.empty.push(id);
+    }
+    hashlife.empty[usize::from(level)]
+}
+
+fn node(hashlife: &HashLife, id: u32) -> Node {
+    hashlife.nodes[id as usize]
+}
+
+fn half(level: u8) -> i64 {
+    1i64 << (level - 1)
+}
+
+fn set_alive(hashlife: &mut HashLife, id: u32, x: i64, y: i64) -> u32 {
+    let n = node(hashlife, id);
+    if n.level == 0 {
+        return ALIVE;
+    }
+    if n.level == 1 {
+        let (mut nw, mut ne, mut sw, mut se) = (n.nw, n.ne, n.sw, n.se);
+        match (x >= 0, y >= 0) {
+            (false, true) => nw = ALIVE,
+            (true, true) => ne = ALIVE,
+            (false, false) => sw = ALIVE,
+            (true, false) => se = ALIVE,
+        }
+        return join(hashlife, nw, ne, sw, se);
+    }
+    let quarter = half(n.level - 1);
+    let (mut nw, mut ne, mut sw, mut se) = (n.nw, n.ne, n.sw, n.se);
+    match (x >= 0, y >= 0) {
+        (false, true) => nw = set_alive(hashlife, nw, x + quarter, y - quarter),
+        (true, true) => ne = set_alive(hashlife, ne, x - quarter, y - quarter),
+        (false, false) => sw = set_alive(hashlife, sw, x + quarter, y + quarter),
+        (true, false) => se = set_alive(hashlife, se, x - quarter, y + quarter),
+    }
+    join(hashlife, nw, ne, sw, se)
+}
+
+fn expand(hashlife: &mut HashLife) {
+    let root = node(hashlife, hashlife.root);
+    let e = empty(hashlife, root.level - 1);
+    let nw = join(hashlife, e, e, e, root.nw);
+    let ne = join(hashlife, e, e, root.ne, e);
+    let sw = join(hashlife, e, root.sw, e, e);
+    let se = join(hashlife, root.se, e, e, e);
+    hashlife.root = join(hashlife, nw, ne, sw, se);
+}
+
+fn centered(hashlife: &mut HashLife, id: u32) -> u32 {
+    let n = node(hashlife, id);
+    let (nw, ne, sw, se) =
+        (node(hashlife, n.nw), node(hashlife, n.ne), node(hashlife, n.sw), node(hashlife, n.se));
+    join(hashlife, nw.se, ne.sw, sw.ne, se.nw)
+}
+
+fn base_step(hashlife: &mut HashLife, id: u32) -> u32 {
+    let n = node(hashlife, id);
+    let mut grid = [[false; 4]; 4];
+    for (quadrant, col, row) in [(n.nw, 0, 0), (n.ne, 2, 0), (n.sw, 0, 2), (n.se, 2, 2)] {
+        let q = node(hashlife, quadrant);
+        grid[row][col] = q.nw == ALIVE;
+        grid[row][col + 1] = q.ne == ALIVE;
+        grid[row + 1][col] = q.sw == ALIVE;
+        grid[row + 1][col + 1] = q.se == ALIVE;
+    }
+    let mut next = [DEAD; 4];
+    for (i, (row, col)) in [(1, 1), (1, 2), (2, 1), (2, 2)].into_iter().enumerate() {
+        let number_of_alive_neighbors = grid[row - 1..=row + 1]
+            .iter()
+            .flat_map(|cells| &cells[col - 1..=col + 1])
+            .filter(|alive| **alive)
+            .count() as u32
+            - u32::from(grid[row][col]);
+        let counts = if grid[row][col] { &hashlife.rule.survival } else { &hashlife.rule.birth };
+        if rule_count_matches(counts, number_of_alive_neighbors) {
+            next[i] = ALIVE;
+        }
+    }
+    join(hashlife, next[0], next[1], next[2], next[3])
+}
+
+fn maybe_compact(hashlife: &mut HashLife) {
+    if hashlife.nodes.len() > hashlife.collect_at {
+        compact(hashlife);
+        hashlife.collect_at = hashlife.node_limit.max(hashlife.nodes.len() * 2);
+    }
+}
+
+fn step_pinned(hashlife: &mut HashLife, slot: usize, j: u8) {
+    maybe_compact(hashlife);
+    let result = step(hashlife, hashlife.pinned[slot], j);
+    hashlife.pinned[slot] = result;
+}
+
+fn step(hashlife: &mut HashLife, id: u32, j: u8) -> u32 {
+    let n = node(hashlife, id);
+    if n.population == 0 {
+        return empty(hashlife, n.level - 1);
+    }
+    if let Some(result) = hashlife.results.get(&(id, j)) {
+        return *result;
+    }
+    if n.level == 2 {
+        let result = base_step(hashlife, id);
+        hashlife.results.insert((id, j), result);
+        return result;
+    }
+    let (nw, ne, sw, se) =
+        (node(hashlife, n.nw), node(hashlife, n.ne), node(hashlife, n.sw), node(hashlife, n.se));
+    let pieces = [
+        n.nw,
+        join(hashlife, nw.ne, ne.nw, nw.se, ne.sw),
+        n.ne,
+        join(hashlife, nw.sw, nw.se, sw.nw, sw.ne),
+        centered(hashlife, id),
+        join(hashlife, ne.sw, ne.se, se.nw, se.ne),
+        n.sw,
+        join(hashlife, sw.ne, se.nw, sw.se, se.sw),
+        n.se,
+    ];
+    let base = hashlife.pinned.len();
+    hashlife.pinned.push(id);
+    hashlife.pinned.extend(pieces);
+    let r = base + 1;
+    for i in 0..9 {
+        if j == n.level - 2 {
+            step_pinned(hashlife, r + i, n.level - 3);
+        } else {
+            let piece = centered(hashlife, hashlife.pinned[r + i]);
+            hashlife.pinned[r + i] = piece;
+        }
+    }
+    let p = &hashlife.pinned[r..r + 9];
+    let quadrants = [
+        (p[0], p[1], p[3], p[4]),
+        (p[1], p[2], p[4], p[5]),
+        (p[3], p[4], p[6], p[7]),
+        (p[4], p[5], p[7], p[8]),
+    ];
+    for (a, b, c, d) in quadrants {
+        let quadrant = join(hashlife, a, b, c, d);
+        hashlife.pinned.push(quadrant);
+    }
+    let next_j = j.min(n.level - 3);
+    for i in 0..4 {
+        step_pinned(hashlife, r + 9 + i, next_j);
+    }
+    let q = &hashlife.pinned[r + 9..r + 13];
+    let (a, b, c, d) = (q[0], q[1], q[2], q[3]);
+    let result = join(hashlife, a, b, c, d);
+    let id = hashlife.pinned[base];
+    hashlife.pinned.truncate(base);
+    if hashlife.results.len() >= hashlife.node_limit {
+        hashlife.results.clear();
+    }
+    hashlife.results.insert((id, j), result);
+    result
+}
+
+fn is_padded(hashlife: &mut HashLife) -> bool {
+    let root = node(hashlife, hashlife.root);
+    let inner = centered(hashlife, hashlife.root);
+    let inner = centered(hashlife, inner);
+    node(hashlife, inner).population == root.population
+}
+
+fn compact(hashlife: &mut HashLife) {
+    let old = std::mem::take(&mut hashlife.nodes);
+    hashlife.index.clear();
+    hashlife.nodes.extend([leaf(0), leaf(1)]);
+    hashlife.empty = vec![DEAD];
+    let mut remap: HashMap<u32, u32> = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+    let mut stack: Vec<(u32, bool)> =
+        hashlife.pinned.iter().chain([&hashlife.root]).map(|id| (*id, false)).collect();
+    while let Some((id, visited)) = stack.pop() {
+        if remap.contains_key(&id) {
+            continue;
+        }
+        let n = old[id as usize];
+        if visited {
+            let new_id = join(hashlife, remap[&n.nw], remap[&n.ne], remap[&n.sw], remap[&n.se]);
+            remap.insert(id, new_id);
+        } else {
+            stack.push((id, true));
+            stack.extend([(n.nw, false), (n.ne, false), (n.sw, false), (n.se, false)]);
+        }
+    }
+    hashlife.root = remap[&hashlife.root];
+    for id in hashlife.pinned.iter_mut() {
+        *id = remap[id];
+    }
+    hashlife.results = std::mem::take(&mut hashlife.results)
+        .into_iter()
+        .filter_map(|((id, j), result)| Some(((*remap.get(&id)?, j), *remap.get(&result)?)))
+        .collect();
+    hashlife.collections += 1;
+}
+
+fn contains(hashlife: &HashLife, x: i64, y: i64) -> bool {
+    let h = half(node(hashlife, hashlife.root).level);
+    x >= -h && x < h && y >= -h && y < h
+}
+
+pub fn hashlife_try_from_universe(
+    universe: &Universe,
+    rule: &Rule,
+) -> Result<HashLife, HashLifeErr> {
+    if !rule_is_life_like(rule) || universe.background != State::Dead {
+        return Err(HashLifeErr::UnsupportedRule(UnsupportedRuleErr));
+    }
+    if universe.topology != Topology::Plane {
+        return Err(HashLifeErr::UnsupportedTopology(UnsupportedTopologyErr));
+    }
+    let mut hashlife = HashLife {
+        rule: rule.clone(),
+        nodes: vec![leaf(0), leaf(1)],
+        index: HashMap::new(),
+        results: HashMap::new(),
+        empty: vec![DEAD],
+        root: DEAD,
+        pinned: Vec::new(),
+        node_limit: DEFAULT_NODE_LIMIT,
+        collect_at: DEFAULT_NODE_LIMIT,
+        collections: 0,
+        age: universe.age,
+    };
+    hashlife.root = empty(&mut hashlife, 3);
+    for (point, state) in universe.value.iter() {
+        if state != &State::Alive {
+            continue;
+        }
+        let (x, y) = (i64::from(point.x), i64::from(point.y));
+        while !contains(&hashlife, x, y) {
+            expand(&mut hashlife);
+        }
+        let root = hashlife.root;
+        hashlife.root = set_alive(&mut hashlife, root, x, y);
+    }
+    Ok(hashlife)
+}
+
+fn collect(
+    hashlife: &HashLife,
+    id: u32,
+    x: i64,
+    y: i64,
+    value: &mut HashMap<CartesianPoint, State>,
+) -> Result<(), HashLifeErr> {
+    let n = node(hashlife, id);
+    if n.population == 0 {
+        return Ok(());
+    }
+    if n.level == 0 {
+        let (Ok(x), Ok(y)) = (i32::try_from(x), i32::try_from(y)) else {
+            return Err(HashLifeErr::PatternTooLarge(PatternTooLargeErr));
+        };
+        value.insert(CartesianPoint::of(x, y), State::Alive);
+        return Ok(());
+    }
+    if n.level == 1 {
+        collect(hashlife, n.nw, x - 1, y, value)?;
+        collect(hashlife, n.ne, x, y, value)?;
+        collect(hashlife, n.sw, x - 1, y - 1, value)?;
+        return collect(hashlife, n.se, x, y - 1, value);
+    }
+    let quarter = half(n.level - 1);
+    collect(hashlife, n.nw, x - quarter, y + quarter, value)?;
+    collect(hashlife, n.ne, x + quarter, y + quarter, value)?;
+    collect(hashlife, n.sw, x - quarter, y - quarter, value)?;
+    collect(hashlife, n.se, x + quarter, y - quarter, value)
+}
+
+pub fn hashlife_to_universe(hashlife: &HashLife) -> Result<Universe, HashLifeErr> {
+    let mut value = HashMap::new();
+    collect(hashlife, hashlife.root, 0, 0, &mut value)?;
+    Ok(Universe { value, age: hashlife.age, ..Default::default() })
+}
+
+pub fn hashlife_population(hashlife: &HashLife) -> u64 {
+    node(hashlife, hashlife.root).population
+}
+
+pub fn hashlife_set_node_limit(hashlife: &mut HashLife, node_limit: usize) {
+    hashlife.node_limit = node_limit;
+    hashlife.collect_at = node_limit;
+}
+
+pub fn hashlife_step_pow2(hashlife: &mut HashLife, k: u8) -> Result<(), HashLifeErr> {
+    if k > MAX_LEVEL - 3 {
+        return Err(HashLifeErr::StepTooLarge(StepTooLargeErr));
+    }
+    while node(hashlife, hashlife.root).level < k + 3 || !is_padded(hashlife) {
+        if node(hashlife, hashlife.root).level >= MAX_LEVEL {
+            return Err(HashLifeErr::PatternTooLarge(PatternTooLargeErr));
+        }
+        expand(hashlife);
+    }
+    let root = hashlife.root;
+    hashlife.root = step(hashlife, root, k);
+    hashlife.age += 1 << k;
+    if hashlife.nodes.len() > hashlife.node_limit {
+        compact(hashlife);
+        hashlife.collect_at = hashlife.node_limit.max(hashlife.nodes.len() * 2);
+    }
+    Ok(())
+}
+
+fn hashlife_matches(hashlife: &HashLife, universe: &Universe, rule: &Rule) -> bool {
+    hashlife.rule == *rule
+        && hashlife.age == universe.age
+        && hashlife_population(hashlife) == universe.value.len() as u64
+        && hashlife_to_universe(hashlife).is_ok_and(|current| current.value == universe.value)
+}
+
+pub fn universe_iterate_pow2(
+    universe: &mut Universe,
+    cache: &mut Option<HashLife>,
+    rule: &Rule,
+    k: u8,
+) -> Result<(), HashLifeErr> {
+    let hashlife = match cache.take_if(|hashlife| hashlife_matches(hashlife, universe, rule)) {
+        Some(hashlife) => cache.insert(hashlife),
+        None => cache.insert(hashlife_try_from_universe(universe, rule)?),
+    };
+    hashlife_step_pow2(hashlife, k)?;
+    *universe = hashlife_to_universe(hashlife)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DEFAULT_NODE_LIMIT, HashLifeErr, MAX_LEVEL, PatternTooLargeErr, StepTooLargeErr,
+        UnsupportedRuleErr, UnsupportedTopologyErr, expand, hashlife_population,
+        hashlife_set_node_limit, hashlife_step_pow2, hashlife_to_universe,
+        hashlife_try_from_universe, node, set_alive, universe_iterate_pow2,
+    };
+    use crate::{
+        preset::get_preset,
+        rule::{Rule, rule_from_str},
+        topology::{Bounds, Topology},
+        universe::{CartesianPoint, Universe, universe_from_str, universe_iterate},
+    };
+
+    fn iterate(universe: &Universe, rule: &Rule, n: u64) -> Universe {
+        let mut universe = universe.clone();
+        for _ in 0..n {
+            universe_iterate(&mut universe, rule);
+        }
+        universe
+    }
+
+    #[test]
+    fn hashlife_err() {
+        assert_eq!(
+            UnsupportedRuleErr.to_string(),
+            "HashLife supports only two-state rules on the Moore neighborhood of radius 1"
+        );
+        assert_eq!(
+            UnsupportedTopologyErr.to_string(),
+            "HashLife supports only the unbounded plane"
+        );
+        assert_eq!(
+            HashLifeErr::UnsupportedRule(UnsupportedRuleErr).to_string(),
+            UnsupportedRuleErr.to_string()
+        );
+        assert_eq!(
+            hashlife_try_from_universe(&Universe::default(), &rule_from_str("B2/S/C3")).err(),
+            Some(HashLifeErr::UnsupportedRule(UnsupportedRuleErr))
+        );
+        assert_eq!(
+            hashlife_try_from_universe(&Universe::default(), &rule_from_str("B2/SV")).err(),
+            Some(HashLifeErr::UnsupportedRule(UnsupportedRuleErr))
+        );
+        let torus =
+            Universe { topology: Topology::Torus(Bounds::of(-4, -4, 3, 3)), ..Default::default() };
+        assert_eq!(
+            hashlife_try_from_universe(&torus, &Rule::default()).err(),
+            Some(HashLifeErr::UnsupportedTopology(UnsupportedTopologyErr))
+        );
+    }
+
+    #[test]
+    fn hashlife_round_trip() {
+        for preset in ["block", "blinker", "glider", "gosper_glider_gun", "puffer1"] {
+            let universe = get_preset(preset);
+            let hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+            assert_eq!(hashlife_population(&hashlife), universe.value.len() as u64);
+            assert_eq!(hashlife_to_universe(&hashlife).unwrap(), universe);
+        }
+        let empty = hashlife_try_from_universe(&Universe::default(), &Rule::default()).unwrap();
+        assert_eq!(hashlife_to_universe(&empty).unwrap(), Universe::default());
+    }
+
+    #[test]
+    fn hashlife_blinker() {
+        let universe = universe_from_str(["⬛⬛⬛", "⬜⬜⬜", "⬛⬛⬛"]);
+        let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        assert_eq!(hashlife_step_pow2(&mut hashlife, 0), Ok(()));
+        assert_eq!(
+            hashlife_to_universe(&hashlife).unwrap(),
+            iterate(&universe, &Rule::default(), 1)
+        );
+        assert_eq!(hashlife_step_pow2(&mut hashlife, 0), Ok(()));
+        assert_eq!(
+            hashlife_to_universe(&hashlife).unwrap(),
+            iterate(&universe, &Rule::default(), 2)
+        );
+    }
+
+    #[test]
+    fn hashlife_matches_universe_iterate() {
+        for preset in ["glider", "r_pentomino", "gosper_glider_gun", "puffer1"] {
+            let universe = get_preset(preset);
+            let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+            let mut generations = 0;
+            for k in 0..7 {
+                assert_eq!(hashlife_step_pow2(&mut hashlife, k), Ok(()));
+                generations += 1 << k;
+                assert_eq!(
+                    hashlife_to_universe(&hashlife).unwrap(),
+                    iterate(&universe, &Rule::default(), generations)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hashlife_other_rules() {
+        let universe = get_preset("r_pentomino");
+        for rule in ["B36/S23", "B3/S012345678", "B1357/S1357"] {
+            let rule = rule_from_str(rule);
+            let mut hashlife = hashlife_try_from_universe(&universe, &rule).unwrap();
+            assert_eq!(hashlife_step_pow2(&mut hashlife, 4), Ok(()));
+            assert_eq!(hashlife_to_universe(&hashlife).unwrap(), iterate(&universe, &rule, 16));
+        }
+    }
+
+    #[test]
+    fn hashlife_eviction() {
+        let universe = get_preset("gosper_glider_gun");
+        let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        hashlife_set_node_limit(&mut hashlife, 64);
+        for _ in 0..4 {
+            assert_eq!(hashlife_step_pow2(&mut hashlife, 5), Ok(()));
+            assert!(hashlife.results.len() <= 64);
+            assert!(hashlife.pinned.is_empty());
+        }
+        assert_eq!(
+            hashlife_to_universe(&hashlife).unwrap(),
+            iterate(&universe, &Rule::default(), 128)
+        );
+    }
+
+    #[test]
+    fn hashlife_eviction_within_step() {
+        let universe = get_preset("gosper_glider_gun");
+        let mut unbounded = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        assert_eq!(hashlife_step_pow2(&mut unbounded, 8), Ok(()));
+        assert!(unbounded.nodes.len() > 2000);
+        let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        hashlife_set_node_limit(&mut hashlife, 1000);
+        assert_eq!(hashlife_step_pow2(&mut hashlife, 8), Ok(()));
+        assert!(hashlife.collections > 1);
+        assert!(hashlife.nodes.len() <= 1000);
+        assert_eq!(
+            hashlife_to_universe(&hashlife).unwrap(),
+            hashlife_to_universe(&unbounded).unwrap()
+        );
+        assert_eq!(
+            hashlife_to_universe(&hashlife).unwrap(),
+            iterate(&universe, &Rule::default(), 256)
+        );
+    }
+
+    #[test]
+    fn hashlife_large_step() {
+        let universe = get_preset("gosper_glider_gun");
+        let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        assert_eq!(hashlife_step_pow2(&mut hashlife, 20), Ok(()));
+        assert_eq!(hashlife.age, 1 << 20);
+        let mut stepped = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        for _ in 0..1 << 8 {
+            assert_eq!(hashlife_step_pow2(&mut stepped, 12), Ok(()));
+        }
+        assert_eq!(stepped.age, 1 << 20);
+        assert_eq!(hashlife_population(&stepped), hashlife_population(&hashlife));
+        assert_eq!(
+            hashlife_to_universe(&stepped).unwrap(),
+            hashlife_to_universe(&hashlife).unwrap()
+        );
+    }
+
+    #[test]
+    fn hashlife_too_large() {
+        assert_eq!(
+            StepTooLargeErr.to_string(),
+            "HashLife supports only steps of up to 2^29 generations"
+        );
+        assert_eq!(PatternTooLargeErr.to_string(), "The pattern grew too large for HashLife");
+        let universe = get_preset("glider");
+        let mut hashlife = hashlife_try_from_universe(&universe, &Rule::default()).unwrap();
+        assert_eq!(
+            hashlife_step_pow2(&mut hashlife, 30),
+            Err(HashLifeErr::StepTooLarge(StepTooLargeErr))
+        );
+        assert_eq!(hashlife.age, 0);
+        let far =
+            Universe::from([CartesianPoint::of(i32::MIN, 0), CartesianPoint::of(i32::MAX, 0)]);
+        let mut hashlife = hashlife_try_from_universe(&far, &rule_from_str("B1/S")).unwrap();
+        assert_eq!(
+            hashlife_step_pow2(&mut hashlife, 0),
+            Err(HashLifeErr::PatternTooLarge(PatternTooLargeErr))
+        );
+        assert_eq!(hashlife.age, 0);
+    }
+
+    #[test]
+    fn hashlife_to_universe_out_of_range() {
+        let mut hashlife =
+            hashlife_try_from_universe(&Universe::default(), &Rule::default()).unwrap();
+        while node(&hashlife, hashlife.root).level <= MAX_LEVEL {
+            expand(&mut hashlife);
+        }
+        let root = hashlife.root;
+        hashlife.root = set_alive(&mut hashlife, root, 1 << 31, 0);
+        assert_eq!(
+            hashlife_to_universe(&hashlife),
+            Err(HashLifeErr::PatternTooLarge(PatternTooLargeErr))
+        );
+    }
+
+    #[test]
+    fn test_universe_iterate_pow2() {
+        let mut universe = get_preset("r_pentomino");
+        let mut cache = None;
+        let expected = iterate(&universe, &Rule::default(), 64);
+        assert_eq!(universe_iterate_pow2(&mut universe, &mut cache, &Rule::default(), 6), Ok(()));
+        assert_eq!(universe, expected);
+        assert_eq!(
+            universe_iterate_pow2(&mut universe, &mut cache, &Rule::default(), 30),
+            Err(HashLifeErr::StepTooLarge(StepTooLargeErr))
+        );
+        assert_eq!(universe, expected);
+    }
+
+    #[test]
+    fn universe_iterate_pow2_cache() {
+        let mut universe = get_preset("r_pentomino");
+        let mut cache = None;
+        assert_eq!(universe_iterate_pow2(&mut universe, &mut cache, &Rule::default(), 4), Ok(()));
+        hashlife_set_node_limit(cache.as_mut().unwrap(), 1 << 20);
+        assert_eq!(universe_iterate_pow2(&mut universe, &mut cache, &Rule::default(), 4), Ok(()));
+        assert_eq!(cache.as_ref().map(|hashlife| hashlife.node_limit), Some(1 << 20));
+        assert_eq!(universe, iterate(&get_preset("r_pentomino"), &Rule::default(), 32));
+
+        let point = universe.value.keys().next().unwrap().clone();
+        universe.value.remove(&point);
+        let expected = iterate(&universe, &Rule::default(), 16);
+        assert_eq!(universe_iterate_pow2(&mut universe, &mut cache, &Rule::default(), 4), Ok(()));
+        assert_eq!(cache.as_ref().map(|hashlife| hashlife.node_limit), Some(DEFAULT_NODE_LIMIT));
+        assert_eq!(universe, expected);
+
+        let rule = rule_from_str("B36/S23");
+        let expected = iterate(&universe, &rule, 16);
+        assert_eq!(universe_iterate_pow2(&mut universe, &mut cache, &rule, 4), Ok(()));
+        assert_eq!(universe, expected);
+    }
+}
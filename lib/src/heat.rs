@@ -0,0 +1,123 @@
+use crate::{
+    cell::State,
+    rule::Rule,
+    universe::{CartesianPoint, Universe, universe_get_value, universe_iterate},
+};
+use std::collections::{HashMap, HashSet};
+
+const HEAT_MIN_VALUE: f64 = 1e-3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HeatMetric {
+    Alive,
+    Changes,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct HeatCell {
+    pub alive: f64,
+    pub changes: f64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Heat {
+    pub cells: HashMap<CartesianPoint, HeatCell>,
+    pub decay: f64,
+    pub generations: u64,
+}
+
+impl Default for Heat {
+    fn default() -> Self {
+        Heat { cells: HashMap::new(), decay: 1.0, generations: 0 }
+    }
+}
+
+pub fn heat_cell_value(cell: &HeatCell, metric: HeatMetric) -> f64 {
+    match metric {
+        HeatMetric::Alive => cell.alive,
+        HeatMetric::Changes => cell.changes,
+    }
+}
+
+pub fn heat_accumulate(heat: &mut Heat, previous: &Universe, universe: &Universe) {
+    if heat.decay < 1.0 {
+        for cell in heat.cells.values_mut() {
+            cell.alive *= heat.decay;
+            cell.changes *= heat.decay;
+        }
+        heat.cells.retain(|_, cell| cell.alive >= HEAT_MIN_VALUE || cell.changes >= HEAT_MIN_VALUE);
+    }
+    let points: HashSet<&CartesianPoint> =
+        previous.value.keys().chain(universe.value.keys()).collect();
+    for point in points {
+        let state = universe_get_value(universe, point);
+        let changed = universe_get_value(previous, point) != state;
+        if state != State::Alive && !changed {
+            continue;
+        }
+        let cell = heat.cells.entry(point.clone()).or_default();
+        if state == State::Alive {
+            cell.alive += 1.0;
+        }
+        if changed {
+            cell.changes += 1.0;
+        }
+    }
+    heat.generations += 1;
+}
+
+pub fn heat_iterate(heat: &mut Heat, universe: &mut Universe, rule: &Rule) {
+    let previous = universe.clone();
+    universe_iterate(universe, rule);
+    heat_accumulate(heat, &previous, universe);
+}
+
+pub fn heat_clear(heat: &mut Heat) {
+    heat.cells.clear();
+    heat.generations = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Heat, HeatCell, HeatMetric, heat_accumulate, heat_cell_value, heat_clear, heat_iterate,
+    };
+    use crate::{
+        preset::get_preset,
+        rule::Rule,
+        universe::{CartesianPoint, Universe},
+    };
+
+    #[test]
+    fn test_heat_iterate() {
+        let mut heat = Heat::default();
+        let mut universe = get_preset("blinker");
+        for _ in 0..4 {
+            heat_iterate(&mut heat, &mut universe, &Rule::default());
+        }
+        assert_eq!(heat.generations, 4);
+        assert_eq!(heat.cells.len(), 5);
+        assert_eq!(heat.cells[&CartesianPoint::of(0, 0)], HeatCell { alive: 4.0, changes: 0.0 });
+        assert_eq!(heat.cells[&CartesianPoint::of(1, 0)], HeatCell { alive: 2.0, changes: 4.0 });
+        assert_eq!(heat.cells[&CartesianPoint::of(0, 1)], HeatCell { alive: 2.0, changes: 4.0 });
+        heat_clear(&mut heat);
+        assert_eq!(heat, Heat::default());
+    }
+
+    #[test]
+    fn test_heat_decay() {
+        let mut heat = Heat { decay: 0.5, ..Default::default() };
+        let block = get_preset("block");
+        heat_accumulate(&mut heat, &Universe::default(), &block);
+        heat_accumulate(&mut heat, &block, &block);
+        let cell = &heat.cells[&CartesianPoint::of(0, 0)];
+        assert_eq!(heat_cell_value(cell, HeatMetric::Alive), 1.5);
+        assert_eq!(heat_cell_value(cell, HeatMetric::Changes), 0.5);
+        heat_accumulate(&mut heat, &block, &Universe::default());
+        assert_eq!(heat.cells[&CartesianPoint::of(0, 0)], HeatCell { alive: 0.75, changes: 1.25 });
+        for _ in 0..20 {
+            heat_accumulate(&mut heat, &Universe::default(), &Universe::default());
+        }
+        assert!(heat.cells.is_empty());
+    }
+}
@@ -0,0 +1,496 @@
+use crate::{
+    cell::State,
+    universe::{CartesianPoint, Universe, universe_next_ages},
+};
+use std::collections::VecDeque;
+
+const CELL_SIZE: usize = size_of::<(CartesianPoint, State)>();
+const POINT_SIZE: usize = size_of::<CartesianPoint>();
+const AGE_SIZE: usize = size_of::<(CartesianPoint, u64)>();
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Delta {
+    pub changed: Vec<(CartesianPoint, State)>,
+    pub removed: Vec<CartesianPoint>,
+    pub background: State,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Snapshot {
+    Keyframe(Universe),
+    Delta(Delta),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct History {
+    pub keyframe_interval: u64,
+    pub memory_budget: usize,
+    snapshots: VecDeque<Snapshot>,
+    first_age: u64,
+    last: Option<Universe>,
+    memory: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            keyframe_interval: 32,
+            memory_budget: 64 * 1024 * 1024,
+            snapshots: VecDeque::new(),
+            first_age: 0,
+            last: None,
+            memory: 0,
+        }
+    }
+}
+
+fn snapshot_memory(snapshot: &Snapshot) -> usize {
+    match snapshot {
+        Snapshot::Keyframe(universe) => {
+            universe.value.len() * CELL_SIZE
+                + universe.ages.as_ref().map_or(0, |ages| ages.len() * AGE_SIZE)
+        }
+        Snapshot::Delta(delta) => {
+            delta.changed.len() * CELL_SIZE + delta.removed.len() * POINT_SIZE
+        }
+    }
+}
+
+fn delta_of(previous: &Universe, universe: &Universe) -> Delta {
+    Delta {
+        changed: universe
+            .value
+            .iter()
+            .filter(|(point, state)| previous.value.get(point) != Some(state))
+            .map(|(point, state)| (point.clone(), *state))
+            .collect(),
+        removed: previous
+            .value
+            .keys()
+            .filter(|point| !universe.value.contains_key(point))
+            .cloned()
+            .collect(),
+        background: universe.background,
+    }
+}
+
+fn same_cells(a: &Universe, b: &Universe) -> bool {
+    a.age == b.age && a.background == b.background && a.topology == b.topology && a.value == b.value
+}
+
+fn is_last(history: &History, universe: &Universe) -> bool {
+    history.last.as_ref().is_some_and(|last| same_cells(last, universe))
+}
+
+fn needs_keyframe(history: &History) -> bool {
+    let since_keyframe = history
+        .snapshots
+        .iter()
+        .rev()
+        .position(|snapshot| matches!(snapshot, Snapshot::Keyframe(_)))
+        .unwrap_or(0) as u64;
+    since_keyframe + 1 >= history.keyframe_interval
+}
+
+fn push_snapshot(history: &mut History, snapshot: Snapshot) {
+    history.memory += snapshot_memory(&snapshot);
+    history.snapshots.push_back(snapshot);
+}
+
+fn restart(history: &mut History, universe: &Universe) {
+    history.snapshots.clear();
+    history.memory = 0;
+    history.first_age = universe.age;
+    push_snapshot(history, Snapshot::Keyframe(universe.clone()));
+    history.last = Some(universe.clone());
+}
+
+fn truncate(history: &mut History, age: u64) {
+    let len = (age - history.first_age) as usize;
+    while history.snapshots.len() > len {
+        let snapshot = history.snapshots.pop_back().unwrap();
+        history.memory -= snapshot_memory(&snapshot);
+    }
+}
+
+fn evict(history: &mut History) {
+    while history.memory > history.memory_budget {
+        let Some(next_keyframe) =
+            history.snapshots.iter().skip(1).position(|s| matches!(s, Snapshot::Keyframe(_)))
+        else {
+            return;
+        };
+        for _ in 0..=next_keyframe {
+            let snapshot = history.snapshots.pop_front().unwrap();
+            history.memory -= snapshot_memory(&snapshot);
+            history.first_age += 1;
+        }
+    }
+}
+
+pub fn history_push(history: &mut History, universe: &Universe) {
+    let Some(newest) = history.last.as_ref().map(|last| last.age) else {
+        restart(history, universe);
+        return;
+    };
+    if universe.age < history.first_age || universe.age > newest + 1 {
+        restart(history, universe);
+        evict(history);
+        return;
+    }
+    if universe.age <= newest {
+        if is_last(history, universe) {
+            return;
+        }
+        if history_get(history, universe.age).is_some_and(|stored| same_cells(&stored, universe)) {
+            return;
+        }
+        truncate(history, universe.age);
+        if history.snapshots.is_empty() {
+            restart(history, universe);
+            evict(history);
+            return;
+        }
+        history.last = history_get(history, universe.age - 1);
+    }
+    let previous = history.last.take().unwrap();
+    if needs_keyframe(history) || previous.topology != universe.topology {
+        push_snapshot(history, Snapshot::Keyframe(universe.clone()));
+    } else {
+        push_snapshot(history, Snapshot::Delta(delta_of(&previous, universe)));
+    }
+    history.last = Some(universe.clone());
+    evict(history);
+}
+
+pub fn history_push_delta(history: &mut History, universe: &Universe, delta: Delta) {
+    let Some(mut last) = history
+        .last
+        .take_if(|last| last.age + 1 == universe.age && last.topology == universe.topology)
+    else {
+        history_push(history, universe);
+        return;
+    };
+    history_apply_delta(&mut last, &delta);
+    last.age = universe.age;
+    if needs_keyframe(history) {
+        push_snapshot(history, Snapshot::Keyframe(last.clone()));
+    } else {
+        push_snapshot(history, Snapshot::Delta(delta));
+    }
+    history.last = Some(last);
+    evict(history);
+}
+
+pub fn history_apply_delta(universe: &mut Universe, delta: &Delta) {
+    let previous = universe.ages.is_some().then(|| universe.clone());
+    for (point, state) in delta.changed.iter() {
+        universe.value.insert(point.clone(), *state);
+    }
+    for point in delta.removed.iter() {
+        universe.value.remove(point);
+    }
+    universe.background = delta.background;
+    if let Some(previous) = previous
+        && let Some((ages, background_age)) =
+            universe_next_ages(&previous, &universe.value, universe.background)
+    {
+        universe.ages = Some(ages);
+        universe.background_age = background_age;
+    }
+}
+
+pub fn history_get(history: &History, age: u64) -> Option<Universe> {
+    if age < history.first_age {
+        return None;
+    }
+    let index = (age - history.first_age) as usize;
+    if index >= history.snapshots.len() {
+        return None;
+    }
+    let keyframe = history
+        .snapshots
+        .range(..=index)
+        .rposition(|snapshot| matches!(snapshot, Snapshot::Keyframe(_)))?;
+    let Snapshot::Keyframe(universe) = &history.snapshots[keyframe] else {
+        return None;
+    };
+    let mut universe = universe.clone();
+    for snapshot in history.snapshots.range(keyframe + 1..=index) {
+        if let Snapshot::Delta(delta) = snapshot {
+            history_apply_delta(&mut universe, delta);
+        }
+    }
+    universe.age = age;
+    Some(universe)
+}
+
+pub fn history_range(history: &History) -> Option<(u64, u64)> {
+    history.last.as_ref().map(|last| (history.first_age, last.age))
+}
+
+pub fn history_memory(history: &History) -> usize {
+    history.memory
+}
+
+pub fn history_jump(history: &History, universe: &mut Universe, age: u64) -> bool {
+    match history_get(history, age) {
+        Some(value) => {
+            *universe = value;
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn history_step_back(history: &History, universe: &mut Universe) -> bool {
+    universe.age > 0 && history_jump(history, universe, universe.age - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Delta, History, history_apply_delta, history_get, history_jump, history_memory,
+        history_push, history_push_delta, history_range, history_step_back,
+    };
+    use crate::{
+        cell::State,
+        preset::get_preset,
+        rule::{Rule, rule_from_str},
+        topology::{Bounds, Topology},
+        universe::{
+            CartesianPoint, Universe, universe_iterate, universe_toggle, universe_track_ages,
+        },
+    };
+
+    fn run(universe: &Universe, n: u64) -> (History, Vec<Universe>) {
+        let mut history = History::default();
+        let mut universe = universe.clone();
+        let mut states = vec![universe.clone()];
+        history_push(&mut history, &universe);
+        for _ in 0..n {
+            universe_iterate(&mut universe, &Rule::default());
+            history_push(&mut history, &universe);
+            states.push(universe.clone());
+        }
+        (history, states)
+    }
+
+    #[test]
+    fn test_history_default() {
+        let history = History::default();
+        assert_eq!(history.keyframe_interval, 32);
+        assert_eq!(history.memory_budget, 64 * 1024 * 1024);
+        assert_eq!(history_range(&history), None);
+        assert_eq!(history_memory(&history), 0);
+        assert_eq!(history_get(&history, 0), None);
+    }
+
+    #[test]
+    fn test_history_get() {
+        let (history, states) = run(&get_preset("gosper_glider_gun"), 100);
+        assert_eq!(history_range(&history), Some((0, 100)));
+        for state in states.iter() {
+            assert_eq!(history_get(&history, state.age).as_ref(), Some(state));
+        }
+        assert_eq!(history_get(&history, 101), None);
+    }
+
+    #[test]
+    fn history_background() {
+        let rule = rule_from_str("B0/S");
+        let mut history = History::default();
+        let mut universe = get_preset("glider");
+        let mut states = vec![universe.clone()];
+        history_push(&mut history, &universe);
+        for _ in 0..5 {
+            universe_iterate(&mut universe, &rule);
+            history_push(&mut history, &universe);
+            states.push(universe.clone());
+        }
+        for state in states.iter() {
+            assert_eq!(history_get(&history, state.age).as_ref(), Some(state));
+        }
+    }
+
+    #[test]
+    fn history_ages() {
+        let mut universe = get_preset("gosper_glider_gun");
+        universe_track_ages(&mut universe, true);
+        let (history, states) = run(&universe, 40);
+        assert!(states[40].ages.is_some());
+        for state in states.iter() {
+            assert_eq!(history_get(&history, state.age).as_ref(), Some(state));
+        }
+        let mut history = history;
+        history_push(&mut history, &states[2]);
+        assert_eq!(history_range(&history), Some((0, 40)));
+        let mut universe = states[40].clone();
+        assert!(history_step_back(&history, &mut universe));
+        assert_eq!(universe, states[39]);
+    }
+
+    #[test]
+    fn history_ages_background() {
+        let rule = rule_from_str("B0/S");
+        let mut history = History { keyframe_interval: 3, ..Default::default() };
+        let mut universe = get_preset("glider");
+        universe_track_ages(&mut universe, true);
+        let mut states = vec![universe.clone()];
+        history_push(&mut history, &universe);
+        for _ in 0..7 {
+            universe_iterate(&mut universe, &rule);
+            history_push(&mut history, &universe);
+            states.push(universe.clone());
+        }
+        for state in states.iter() {
+            assert_eq!(history_get(&history, state.age).as_ref(), Some(state));
+        }
+    }
+
+    #[test]
+    fn test_history_step_back() {
+        let (history, states) = run(&get_preset("r_pentomino"), 10);
+        let mut universe = states[10].clone();
+        for age in (0..10).rev() {
+            assert!(history_step_back(&history, &mut universe));
+            assert_eq!(universe, states[age]);
+        }
+        assert!(!history_step_back(&history, &mut universe));
+        assert_eq!(universe, states[0]);
+    }
+
+    #[test]
+    fn test_history_jump() {
+        let (history, states) = run(&get_preset("glider"), 40);
+        let mut universe = states[40].clone();
+        assert!(history_jump(&history, &mut universe, 7));
+        assert_eq!(universe, states[7]);
+        assert!(history_jump(&history, &mut universe, 33));
+        assert_eq!(universe, states[33]);
+        assert!(!history_jump(&history, &mut universe, 41));
+        assert_eq!(universe, states[33]);
+    }
+
+    #[test]
+    fn history_push_keeps_future_when_replaying() {
+        let (mut history, states) = run(&get_preset("r_pentomino"), 10);
+        let mut universe = states[10].clone();
+        history_jump(&history, &mut universe, 4);
+        universe_iterate(&mut universe, &Rule::default());
+        history_push(&mut history, &universe);
+        assert_eq!(history_range(&history), Some((0, 10)));
+        assert_eq!(history_get(&history, 10).as_ref(), Some(&states[10]));
+    }
+
+    #[test]
+    fn history_push_branch() {
+        let (mut history, states) = run(&get_preset("r_pentomino"), 10);
+        let mut universe = states[10].clone();
+        history_jump(&history, &mut universe, 4);
+        universe_toggle(&mut universe, CartesianPoint::of(20, 20));
+        history_push(&mut history, &universe);
+        assert_eq!(history_range(&history), Some((0, 4)));
+        assert_eq!(history_get(&history, 4).as_ref(), Some(&universe));
+        assert_eq!(history_get(&history, 3).as_ref(), Some(&states[3]));
+        universe_iterate(&mut universe, &Rule::default());
+        history_push(&mut history, &universe);
+        assert_eq!(history_range(&history), Some((0, 5)));
+        assert_eq!(history_get(&history, 5).as_ref(), Some(&universe));
+    }
+
+    #[test]
+    fn history_push_current() {
+        let (mut history, states) = run(&get_preset("gosper_glider_gun"), 40);
+        let expected = history.clone();
+        history_push(&mut history, &states[40]);
+        assert_eq!(history, expected);
+        let mut universe = states[40].clone();
+        universe_toggle(&mut universe, CartesianPoint::of(30, 30));
+        history_push(&mut history, &universe);
+        assert_eq!(history_range(&history), Some((0, 40)));
+        assert_eq!(history_get(&history, 40).as_ref(), Some(&universe));
+    }
+
+    #[test]
+    fn history_push_gap() {
+        let (mut history, states) = run(&get_preset("glider"), 5);
+        let mut universe = states[5].clone();
+        universe.age = 50;
+        history_push(&mut history, &universe);
+        assert_eq!(history_range(&history), Some((50, 50)));
+        assert_eq!(history_get(&history, 5), None);
+    }
+
+    #[test]
+    fn history_push_topology() {
+        let (mut history, states) = run(&get_preset("glider"), 3);
+        let mut universe = states[3].clone();
+        universe.topology = Topology::Torus(Bounds::of(-8, -8, 7, 7));
+        universe_iterate(&mut universe, &Rule::default());
+        history_push(&mut history, &universe);
+        assert_eq!(history_get(&history, 4).as_ref(), Some(&universe));
+        assert_eq!(history_get(&history, 3).as_ref(), Some(&states[3]));
+    }
+
+    #[test]
+    fn history_memory_budget() {
+        let mut history =
+            History { keyframe_interval: 8, memory_budget: 16384, ..Default::default() };
+        let mut universe = get_preset("gosper_glider_gun");
+        history_push(&mut history, &universe);
+        for _ in 0..200 {
+            universe_iterate(&mut universe, &Rule::default());
+            history_push(&mut history, &universe);
+            assert!(history_memory(&history) <= 16384);
+        }
+        let (first, last) = history_range(&history).unwrap();
+        assert!(first > 0);
+        assert_eq!(first % 8, 0);
+        assert_eq!(last, 200);
+        assert_eq!(history_get(&history, 200).as_ref(), Some(&universe));
+        assert_eq!(history_get(&history, first - 1), None);
+    }
+
+    #[test]
+    fn test_history_push_delta() {
+        let (expected, states) = run(&get_preset("gosper_glider_gun"), 70);
+        let mut history = History::default();
+        history_push(&mut history, &states[0]);
+        for pair in states.windows(2) {
+            let delta = Delta {
+                changed: pair[1]
+                    .value
+                    .keys()
+                    .filter(|point| !pair[0].value.contains_key(point))
+                    .map(|point| (point.clone(), State::Alive))
+                    .collect(),
+                removed: pair[0]
+                    .value
+                    .keys()
+                    .filter(|point| !pair[1].value.contains_key(point))
+                    .cloned()
+                    .collect(),
+                background: State::Dead,
+            };
+            let mut universe = pair[0].clone();
+            history_apply_delta(&mut universe, &delta);
+            universe.age += 1;
+            assert_eq!(universe, pair[1]);
+            history_push_delta(&mut history, &universe, delta);
+        }
+        assert_eq!(history_range(&history), history_range(&expected));
+        assert_eq!(history_memory(&history), history_memory(&expected));
+        for state in states.iter() {
+            assert_eq!(history_get(&history, state.age).as_ref(), Some(state));
+        }
+        let mut universe = states[70].clone();
+        universe.age = 90;
+        history_push_delta(
+            &mut history,
+            &universe,
+            Delta { changed: Vec::new(), removed: Vec::new(), background: State::Dead },
+        );
+        assert_eq!(history_range(&history), Some((90, 90)));
+    }
+}
@@ -1,6 +1,20 @@
+pub mod analysis;
 pub mod cell;
+pub mod census;
+pub mod hashlife;
+pub mod heat;
+pub mod history;
 pub mod neighbor;
+pub mod pattern;
 pub mod preset;
+pub mod random;
 pub mod render;
 pub mod render_settings;
+pub mod rle;
+pub mod rule;
+pub mod soup;
+pub mod table;
+pub mod tile;
+pub mod topology;
+pub mod transform;
 pub mod universe;
@@ -1,87 +1,482 @@
 use crate::{
     cell::State,
-    universe::{CartesianPoint, Universe, universe_get_value},
+    rule::Rule,
+    table::{Table, table_offsets},
+    transform::{TRANSFORMS, transform_point},
+    universe::{
+        CartesianPoint, FromStringErr, Universe, universe_get_value, universe_try_from_str,
+    },
 };
+use std::sync::LazyLock;
 
-fn number_of_alive(neighbors: [State; 8]) -> u8 {
-    neighbors.iter().filter(|neighbor| neighbor == &&State::Alive).count() as u8
+pub type NeighborConfiguration = u8;
+
+pub const HENSEL_LETTERS: [&str; 9] =
+    ["", "ce", "ceaikn", "ceaikjqrny", "ceaikjqrnytwz", "ceaikjqrny", "ceaikn", "ce", ""];
+
+const HENSEL_REPRESENTATIVES: [&[u8]; 5] = [
+    &[],
+    &[0b00000001, 0b00000010],
+    &[0b00000101, 0b00001010, 0b00000011, 0b00011000, 0b00010001, 0b00100100],
+    &[
+        0b00100101, 0b00011010, 0b00001011, 0b00000111, 0b00110010, 0b00001101, 0b00001110,
+        0b00100110, 0b00011001, 0b00110001,
+    ],
+    &[
+        0b10100101, 0b01011010, 0b00001111, 0b00011101, 0b00110011, 0b00100111, 0b00111010,
+        0b00110110, 0b00011011, 0b00110101, 0b00111001, 0b00101110, 0b00111100,
+    ],
+];
+
+static HENSEL_CLASSES: LazyLock<[Option<char>; 256]> = LazyLock::new(|| {
+    let offsets: Vec<CartesianPoint> = neighborhood_offsets(&Neighborhood::Moore(1)).collect();
+    let mut classes = [None; 256];
+    for (count, representatives) in HENSEL_REPRESENTATIVES.iter().enumerate() {
+        for (representative, letter) in representatives.iter().zip(HENSEL_LETTERS[count].chars()) {
+            for transform in TRANSFORMS {
+                let configuration = offsets.iter().enumerate().fold(0u8, |acc, (i, offset)| {
+                    if representative & (1 << i) == 0 {
+                        return acc;
+                    }
+                    let image = transform_point(transform, offset);
+                    acc | (1 << offsets.iter().position(|o| o == &image).unwrap())
+                });
+                classes[configuration as usize] = Some(letter);
+                if count < 4 {
+                    classes[!configuration as usize] = Some(letter);
+                }
+            }
+        }
+    }
+    classes
+});
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Neighborhood {
+    Moore(u16),
+    VonNeumann(u16),
+    Hexagonal(u16),
+    Custom(Vec<CartesianPoint>),
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::Moore(1)
+    }
+}
+
+pub fn neighborhood_radius(neighborhood: &Neighborhood) -> u16 {
+    match neighborhood {
+        Neighborhood::Moore(radius) => *radius,
+        Neighborhood::VonNeumann(radius) => *radius,
+        Neighborhood::Hexagonal(radius) => *radius,
+        Neighborhood::Custom(offsets) => offsets
+            .iter()
+            .map(|offset| offset.x.unsigned_abs().max(offset.y.unsigned_abs()))
+            .max()
+            .unwrap_or(0) as u16,
+    }
+}
+
+pub fn neighborhood_offsets(
+    neighborhood: &Neighborhood,
+) -> Box<dyn Iterator<Item = CartesianPoint> + '_> {
+    if let Neighborhood::Custom(offsets) = neighborhood {
+        return Box::new(offsets.iter().cloned());
+    }
+    let radius = i32::from(neighborhood_radius(neighborhood));
+    let square = (-radius..=radius)
+        .flat_map(move |y| (-radius..=radius).map(move |x| CartesianPoint::of(x, -y)))
+        .filter(|point| point.x != 0 || point.y != 0);
+    match neighborhood {
+        Neighborhood::VonNeumann(_) => {
+            Box::new(square.filter(move |point| point.x.abs() + point.y.abs() <= radius))
+        }
+        Neighborhood::Hexagonal(_) => {
+            Box::new(square.filter(move |point| (point.x + point.y).abs() <= radius))
+        }
+        _ => Box::new(square),
+    }
+}
+
+pub fn neighborhood_size(neighborhood: &Neighborhood) -> u32 {
+    match neighborhood {
+        Neighborhood::Moore(radius) => {
+            let side = 2 * u32::from(*radius) + 1;
+            side * side - 1
+        }
+        Neighborhood::VonNeumann(radius) => {
+            let radius = u32::from(*radius);
+            2 * radius * (radius + 1)
+        }
+        Neighborhood::Hexagonal(radius) => {
+            let radius = u32::from(*radius);
+            3 * radius * (radius + 1)
+        }
+        Neighborhood::Custom(offsets) => offsets.len() as u32,
+    }
+}
+
+pub fn neighborhood_try_from_str<const N: usize>(
+    as_str: [&str; N],
+) -> Result<Neighborhood, FromStringErr> {
+    let mut offsets: Vec<CartesianPoint> =
+        universe_try_from_str(as_str)?.value.into_keys().collect();
+    offsets.sort_by_key(|offset| (-offset.y, offset.x));
+    Ok(Neighborhood::Custom(offsets))
+}
+
+pub fn neighborhood_from_str<const N: usize>(as_str: [&str; N]) -> Neighborhood {
+    neighborhood_try_from_str(as_str).unwrap()
 }
 
-fn neighbors_in_rectangular_grid(universe: &Universe, point: &CartesianPoint) -> [State; 8] {
-    [
-        universe_get_value(universe, &CartesianPoint::of(point.x - 1, point.y + 1)),
-        universe_get_value(universe, &CartesianPoint::of(point.x, point.y + 1)),
-        universe_get_value(universe, &CartesianPoint::of(point.x + 1, point.y + 1)),
-        universe_get_value(universe, &CartesianPoint::of(point.x - 1, point.y)),
-        universe_get_value(universe, &CartesianPoint::of(point.x + 1, point.y)),
-        universe_get_value(universe, &CartesianPoint::of(point.x - 1, point.y - 1)),
-        universe_get_value(universe, &CartesianPoint::of(point.x, point.y - 1)),
-        universe_get_value(universe, &CartesianPoint::of(point.x + 1, point.y - 1)),
-    ]
+fn number_of_alive(neighbors: impl Iterator<Item = State>) -> u32 {
+    neighbors.filter(|neighbor| neighbor == &State::Alive).count() as u32
 }
 
-pub fn number_of_alive_from_model(universe: &Universe, point: &CartesianPoint) -> u8 {
-    number_of_alive(neighbors_in_rectangular_grid(universe, point))
+pub fn number_of_alive_from_model(universe: &Universe, rule: &Rule, point: &CartesianPoint) -> u32 {
+    let neighbors = neighborhood_offsets(&rule.neighborhood).map(|offset| {
+        universe_get_value(universe, &CartesianPoint::of(point.x + offset.x, point.y + offset.y))
+    });
+    let middle = rule.middle.then(|| universe_get_value(universe, point));
+    number_of_alive(neighbors.chain(middle))
+}
+
+pub fn neighbor_configuration_from_model(
+    universe: &Universe,
+    point: &CartesianPoint,
+) -> NeighborConfiguration {
+    neighborhood_offsets(&Neighborhood::Moore(1)).enumerate().fold(0, |acc, (i, offset)| {
+        let neighbor = CartesianPoint::of(point.x + offset.x, point.y + offset.y);
+        if universe_get_value(universe, &neighbor) == State::Alive { acc | (1 << i) } else { acc }
+    })
+}
+
+pub fn neighbor_states_from_model(
+    universe: &Universe,
+    table: &Table,
+    point: &CartesianPoint,
+) -> Vec<State> {
+    table_offsets(table)
+        .map(|offset| {
+            universe_get_value(
+                universe,
+                &CartesianPoint::of(point.x + offset.x, point.y + offset.y),
+            )
+        })
+        .collect()
+}
+
+pub fn neighbor_configuration_letter(configuration: NeighborConfiguration) -> Option<char> {
+    HENSEL_CLASSES[configuration as usize]
+}
+
+pub fn neighbor_configurations(count: u32, letter: Option<char>) -> Vec<NeighborConfiguration> {
+    (0..=255)
+        .filter(|configuration: &u8| configuration.count_ones() == count)
+        .filter(|configuration| {
+            letter.is_none() || neighbor_configuration_letter(*configuration) == letter
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::number_of_alive;
-    use crate::cell::State;
+    use super::{
+        HENSEL_LETTERS, Neighborhood, neighbor_configuration_from_model,
+        neighbor_configuration_letter, neighbor_configurations, neighborhood_from_str,
+        neighborhood_offsets, neighborhood_radius, neighborhood_size, neighborhood_try_from_str,
+        number_of_alive, number_of_alive_from_model,
+    };
+    use crate::{
+        cell::State,
+        rule::{Rule, rule_from_str},
+        universe::{CartesianPoint, FromStringErr, InvalidCharacterErr, universe_from_str},
+    };
 
     #[test]
     fn test_number_of_alive() {
         assert_eq!(
-            number_of_alive([
-                State::Dead,
-                State::Dead,
-                State::Dead,
-                State::Dead,
-                State::Dead,
-                State::Dead,
-                State::Dead,
-                State::Dead,
-            ]),
+            number_of_alive(
+                [
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                    State::Dead,
+                ]
+                .into_iter()
+            ),
             0
         );
         assert_eq!(
-            number_of_alive([
-                State::Alive,
-                State::Alive,
-                State::Alive,
-                State::Alive,
-                State::Alive,
-                State::Alive,
-                State::Alive,
-                State::Alive,
-            ]),
+            number_of_alive(
+                [
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                    State::Alive,
+                ]
+                .into_iter()
+            ),
             8
         );
         assert_eq!(
-            number_of_alive([
-                State::Alive,
-                State::Dead,
-                State::Alive,
-                State::Dead,
-                State::Alive,
-                State::Dead,
-                State::Alive,
-                State::Dead
-            ]),
+            number_of_alive(
+                [
+                    State::Alive,
+                    State::Dead,
+                    State::Alive,
+                    State::Dead,
+                    State::Alive,
+                    State::Dead,
+                    State::Alive,
+                    State::Dead
+                ]
+                .into_iter()
+            ),
             4
         );
         assert_eq!(
-            number_of_alive([
-                State::Dead,
-                State::Alive,
-                State::Dead,
-                State::Alive,
-                State::Dead,
-                State::Alive,
-                State::Dead,
-                State::Alive
+            number_of_alive(
+                [
+                    State::Dead,
+                    State::Alive,
+                    State::Dead,
+                    State::Alive,
+                    State::Dead,
+                    State::Alive,
+                    State::Dead,
+                    State::Alive
+                ]
+                .into_iter()
+            ),
+            4
+        );
+        assert_eq!(
+            number_of_alive([State::Alive, State::Dying(2), State::Dying(3)].into_iter()),
+            1
+        );
+    }
+
+    #[test]
+    fn test_neighborhood_offsets() {
+        assert_eq!(
+            neighborhood_offsets(&Neighborhood::Moore(1)).collect::<Vec<CartesianPoint>>(),
+            [
+                CartesianPoint::of(-1, 1),
+                CartesianPoint::of(0, 1),
+                CartesianPoint::of(1, 1),
+                CartesianPoint::of(-1, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(-1, -1),
+                CartesianPoint::of(0, -1),
+                CartesianPoint::of(1, -1),
+            ]
+        );
+        assert_eq!(
+            neighborhood_offsets(&Neighborhood::VonNeumann(1)).collect::<Vec<CartesianPoint>>(),
+            [
+                CartesianPoint::of(0, 1),
+                CartesianPoint::of(-1, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(0, -1),
+            ]
+        );
+        assert_eq!(
+            neighborhood_offsets(&Neighborhood::VonNeumann(2)).collect::<Vec<CartesianPoint>>(),
+            [
+                CartesianPoint::of(0, 2),
+                CartesianPoint::of(-1, 1),
+                CartesianPoint::of(0, 1),
+                CartesianPoint::of(1, 1),
+                CartesianPoint::of(-2, 0),
+                CartesianPoint::of(-1, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(2, 0),
+                CartesianPoint::of(-1, -1),
+                CartesianPoint::of(0, -1),
+                CartesianPoint::of(1, -1),
+                CartesianPoint::of(0, -2),
+            ]
+        );
+        assert_eq!(
+            neighborhood_offsets(&Neighborhood::Hexagonal(1)).collect::<Vec<CartesianPoint>>(),
+            [
+                CartesianPoint::of(-1, 1),
+                CartesianPoint::of(0, 1),
+                CartesianPoint::of(-1, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(0, -1),
+                CartesianPoint::of(1, -1),
+            ]
+        );
+        assert_eq!(
+            neighborhood_offsets(&Neighborhood::Custom(vec![
+                CartesianPoint::of(0, 2),
+                CartesianPoint::of(0, -2),
+            ]))
+            .collect::<Vec<CartesianPoint>>(),
+            [CartesianPoint::of(0, 2), CartesianPoint::of(0, -2)]
+        );
+        assert_eq!(neighborhood_offsets(&Neighborhood::Moore(5)).count(), 120);
+        assert_eq!(neighborhood_offsets(&Neighborhood::Hexagonal(2)).count(), 18);
+    }
+
+    #[test]
+    fn test_neighborhood_radius() {
+        assert_eq!(neighborhood_radius(&Neighborhood::Moore(5)), 5);
+        assert_eq!(neighborhood_radius(&Neighborhood::VonNeumann(2)), 2);
+        assert_eq!(neighborhood_radius(&Neighborhood::Hexagonal(1)), 1);
+        assert_eq!(
+            neighborhood_radius(&Neighborhood::Custom(vec![
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(-3, 2),
+            ])),
+            3
+        );
+        assert_eq!(neighborhood_radius(&Neighborhood::Custom(vec![])), 0);
+    }
+
+    #[test]
+    fn test_neighborhood_try_from_str() {
+        assert_eq!(
+            neighborhood_try_from_str(["⬜⬛⬜", "⬛⬛⬛", "⬜⬛⬜"]),
+            Ok(Neighborhood::Custom(vec![
+                CartesianPoint::of(-1, 1),
+                CartesianPoint::of(1, 1),
+                CartesianPoint::of(-1, -1),
+                CartesianPoint::of(1, -1),
+            ]))
+        );
+        assert_eq!(
+            neighborhood_try_from_str(["⬜x", "⬛⬛"]),
+            Err(FromStringErr::InvalidCharacter(InvalidCharacterErr))
+        );
+        assert_eq!(
+            neighborhood_from_str([
+                "⬛⬛⬜⬛⬛",
+                "⬛⬛⬛⬛⬛",
+                "⬜⬛⬜⬛⬜",
+                "⬛⬛⬛⬛⬛",
+                "⬛⬛⬜⬛⬛"
             ]),
+            Neighborhood::Custom(vec![
+                CartesianPoint::of(0, 2),
+                CartesianPoint::of(-2, 0),
+                CartesianPoint::of(0, 0),
+                CartesianPoint::of(2, 0),
+                CartesianPoint::of(0, -2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_neighborhood_size() {
+        assert_eq!(neighborhood_size(&Neighborhood::Moore(1)), 8);
+        assert_eq!(neighborhood_size(&Neighborhood::Moore(2)), 24);
+        assert_eq!(neighborhood_size(&Neighborhood::Moore(5)), 120);
+        assert_eq!(neighborhood_size(&Neighborhood::VonNeumann(1)), 4);
+        assert_eq!(neighborhood_size(&Neighborhood::VonNeumann(2)), 12);
+        assert_eq!(neighborhood_size(&Neighborhood::VonNeumann(5)), 60);
+        assert_eq!(neighborhood_size(&Neighborhood::Hexagonal(1)), 6);
+        assert_eq!(neighborhood_size(&Neighborhood::Hexagonal(2)), 18);
+        assert_eq!(
+            neighborhood_size(&Neighborhood::Custom(vec![
+                CartesianPoint::of(0, 2),
+                CartesianPoint::of(0, -2),
+            ])),
+            2
+        );
+    }
+
+    #[test]
+    fn test_number_of_alive_from_model() {
+        let universe = universe_from_str([
+            "⬜⬜⬜⬜⬜",
+            "⬜⬛⬛⬛⬛",
+            "⬛⬛⬜⬜⬛",
+            "⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬜",
+        ]);
+        let center = CartesianPoint::of(0, 0);
+        assert_eq!(number_of_alive_from_model(&universe, &Rule::default(), &center), 1);
+        assert_eq!(
+            number_of_alive_from_model(&universe, &rule_from_str("R1,C0,M1,S,B1,NM"), &center),
+            2
+        );
+        assert_eq!(
+            number_of_alive_from_model(&universe, &rule_from_str("R2,C0,M0,S,B1,NM"), &center),
+            8
+        );
+        assert_eq!(
+            number_of_alive_from_model(&universe, &rule_from_str("R2,C0,M0,S,B1,NN"), &center),
+            2
+        );
+        assert_eq!(number_of_alive_from_model(&universe, &rule_from_str("B2/S34H"), &center), 1);
+        assert_eq!(number_of_alive_from_model(&universe, &rule_from_str("B2/S34V"), &center), 1);
+        assert_eq!(
+            number_of_alive_from_model(
+                &universe,
+                &Rule {
+                    neighborhood: neighborhood_from_str([
+                        "⬜⬛⬜⬛⬜",
+                        "⬛⬛⬛⬛⬛",
+                        "⬜⬛⬛⬛⬜",
+                        "⬛⬛⬛⬛⬛",
+                        "⬜⬛⬜⬛⬜",
+                    ]),
+                    ..Default::default()
+                },
+                &center
+            ),
             4
         );
     }
+
+    #[test]
+    fn test_neighbor_configuration_from_model() {
+        let universe = universe_from_str(["⬜⬛⬛", "⬛⬛⬜", "⬛⬜⬛"]);
+        let center = CartesianPoint::of(0, 0);
+        assert_eq!(neighbor_configuration_from_model(&universe, &center), 0b01010001);
+        assert_eq!(neighbor_configuration_letter(0b01010001), Some('k'));
+        assert_eq!(neighbor_configuration_from_model(&universe, &CartesianPoint::of(5, 5)), 0);
+    }
+
+    #[test]
+    fn test_neighbor_configurations() {
+        let sizes = |count, letters: &str| -> Vec<usize> {
+            letters.chars().map(|l| neighbor_configurations(count, Some(l)).len()).collect()
+        };
+        assert_eq!(sizes(1, "ce"), vec![4, 4]);
+        assert_eq!(sizes(2, "ceaikn"), vec![4, 4, 8, 2, 8, 2]);
+        assert_eq!(sizes(4, "ce"), vec![1, 1]);
+        for count in 0..=8 {
+            let total: usize = HENSEL_LETTERS[count as usize]
+                .chars()
+                .map(|letter| neighbor_configurations(count, Some(letter)).len())
+                .sum();
+            assert_eq!(total.max(1), neighbor_configurations(count, None).len());
+        }
+        for configuration in 0..=255u8 {
+            let count = configuration.count_ones();
+            assert_eq!(
+                neighbor_configuration_letter(configuration).is_some(),
+                (1..8).contains(&count)
+            );
+            if count != 4 {
+                assert_eq!(
+                    neighbor_configuration_letter(configuration),
+                    neighbor_configuration_letter(!configuration)
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,441 @@
+use crate::{
+    cell::State,
+    rle::{RleFromStrErr, rle_try_from_str},
+    universe::{
+        CartesianPoint, FromStringErr, Universe, universe_bounds, universe_try_from_string,
+    },
+};
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidCellErr {
+    pub line: usize,
+}
+
+impl fmt::Display for InvalidCellErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: must match the pattern [.O*]", self.line)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidCoordinateErr {
+    pub line: usize,
+}
+
+impl fmt::Display for InvalidCoordinateErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: must match the pattern -?[0-9]+ -?[0-9]+", self.line)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnknownFormatErr;
+
+impl fmt::Display for UnknownFormatErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The pattern must be RLE, plaintext, Life 1.05, Life 1.06 or a grid of [⬜⬛]")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PatternFromStrErr {
+    UnknownFormat(UnknownFormatErr),
+    InvalidCell(InvalidCellErr),
+    InvalidCoordinate(InvalidCoordinateErr),
+    Grid(FromStringErr),
+    Rle(RleFromStrErr),
+}
+
+impl fmt::Display for PatternFromStrErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternFromStrErr::UnknownFormat(err) => err.fmt(f),
+            PatternFromStrErr::InvalidCell(err) => err.fmt(f),
+            PatternFromStrErr::InvalidCoordinate(err) => err.fmt(f),
+            PatternFromStrErr::Grid(err) => err.fmt(f),
+            PatternFromStrErr::Rle(err) => err.fmt(f),
+        }
+    }
+}
+
+type PatternRows<'a> = Vec<(usize, &'a str)>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PatternFormat {
+    Grid,
+    Plaintext,
+    Life105,
+    Life106,
+    Rle,
+}
+
+fn cells_try_from_rows(
+    rows: &[(usize, &str)],
+    origin: Option<CartesianPoint>,
+    value: &mut HashMap<CartesianPoint, State>,
+) -> Result<(), PatternFromStrErr> {
+    let origin = origin.unwrap_or_else(|| {
+        let width = rows.iter().map(|(_, row)| row.chars().count()).max().unwrap_or_default();
+        let width = width as i32;
+        let height = rows.len() as i32;
+        CartesianPoint::of(-width / 2, -height / 2 + height - 1)
+    });
+    for (row, (line, row_str)) in rows.iter().enumerate() {
+        for (col, character) in row_str.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => {
+                    value.insert(
+                        CartesianPoint::of(origin.x + col as i32, origin.y - row as i32),
+                        State::Alive,
+                    );
+                }
+                _ => return Err(PatternFromStrErr::InvalidCell(InvalidCellErr { line: *line })),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn pattern_to_rows(universe: &Universe, alive: char) -> Vec<String> {
+    let Some(bounds) = universe_bounds(universe) else {
+        return Vec::new();
+    };
+    (bounds.min.y..=bounds.max.y)
+        .rev()
+        .map(|y| {
+            let row: String = (bounds.min.x..=bounds.max.x)
+                .map(|x| match universe.value.get(&CartesianPoint::of(x, y)) {
+                    Some(State::Alive) => alive,
+                    _ => '.',
+                })
+                .collect();
+            match row.trim_end_matches('.') {
+                "" => ".".into(),
+                row => row.into(),
+            }
+        })
+        .collect()
+}
+
+pub fn pattern_try_from_plaintext(as_str: &str) -> Result<Universe, PatternFromStrErr> {
+    let rows: PatternRows = as_str
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end()))
+        .filter(|(_, line)| !line.starts_with('!'))
+        .collect();
+    let mut value = HashMap::new();
+    cells_try_from_rows(&rows, None, &mut value)?;
+    Ok(Universe::from(value))
+}
+
+pub fn pattern_to_plaintext(universe: &Universe) -> String {
+    pattern_to_rows(universe, 'O').into_iter().map(|row| row + "\n").collect()
+}
+
+pub fn pattern_try_from_life_105(as_str: &str) -> Result<Universe, PatternFromStrErr> {
+    let mut blocks: Vec<(Option<CartesianPoint>, PatternRows)> = Vec::new();
+    for (index, line) in as_str.lines().enumerate() {
+        let line = line.trim();
+        if let Some(position) = line.strip_prefix("#P") {
+            let invalid =
+                || PatternFromStrErr::InvalidCoordinate(InvalidCoordinateErr { line: index + 1 });
+            let coordinates: Vec<i32> = position
+                .split_whitespace()
+                .map(|value| value.parse::<i32>().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()?;
+            let [x, y] = coordinates[..] else {
+                return Err(invalid());
+            };
+            blocks.push((Some(CartesianPoint::of(x, -y)), Vec::new()));
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if blocks.is_empty() {
+            blocks.push((None, Vec::new()));
+        }
+        if let Some((_, rows)) = blocks.last_mut() {
+            rows.push((index + 1, line));
+        }
+    }
+    let mut value = HashMap::new();
+    for (origin, rows) in blocks {
+        cells_try_from_rows(&rows, origin, &mut value)?;
+    }
+    Ok(Universe::from(value))
+}
+
+pub fn pattern_to_life_105(universe: &Universe) -> String {
+    let mut lines = vec!["#Life 1.05".to_string()];
+    if let Some(bounds) = universe_bounds(universe) {
+        lines.push(format!("#P {} {}", bounds.min.x, -bounds.max.y));
+        lines.extend(pattern_to_rows(universe, '*'));
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn pattern_try_from_life_106(as_str: &str) -> Result<Universe, PatternFromStrErr> {
+    let mut value = HashMap::new();
+    for (index, line) in as_str.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid =
+            || PatternFromStrErr::InvalidCoordinate(InvalidCoordinateErr { line: index + 1 });
+        let coordinates: Vec<i32> = line
+            .split_whitespace()
+            .map(|value| value.parse::<i32>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [x, y] = coordinates[..] else {
+            return Err(invalid());
+        };
+        value.insert(CartesianPoint::of(x, -y), State::Alive);
+    }
+    Ok(Universe::from(value))
+}
+
+pub fn pattern_to_life_106(universe: &Universe) -> String {
+    let mut points: Vec<&CartesianPoint> = universe
+        .value
+        .iter()
+        .filter(|(_, state)| **state == State::Alive)
+        .map(|(point, _)| point)
+        .collect();
+    points.sort_by_key(|point| (-point.y, point.x));
+    let mut lines = vec!["#Life 1.06".to_string()];
+    lines.extend(points.into_iter().map(|point| format!("{} {}", point.x, -point.y)));
+    lines.join("\n") + "\n"
+}
+
+pub fn pattern_format_detect(as_str: &str) -> Option<PatternFormat> {
+    let mut lines = as_str.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = lines.clone().next()?;
+    if first.starts_with("#Life 1.06") {
+        return Some(PatternFormat::Life106);
+    }
+    if first.starts_with("#Life 1.05") {
+        return Some(PatternFormat::Life105);
+    }
+    if first.starts_with('!') {
+        return Some(PatternFormat::Plaintext);
+    }
+    if lines.clone().any(|line| !line.starts_with(['!', '#']) && line.contains(['⬜', '⬛'])) {
+        return Some(PatternFormat::Grid);
+    }
+    let first = lines.find(|line| !line.starts_with('#'))?;
+    if first.starts_with('x') && first.contains('=') {
+        return Some(PatternFormat::Rle);
+    }
+    if first.split_whitespace().count() == 2
+        && first.split_whitespace().all(|value| value.parse::<i32>().is_ok())
+    {
+        return Some(PatternFormat::Life106);
+    }
+    if first.chars().all(|c| matches!(c, '.' | 'O' | '*')) {
+        return Some(PatternFormat::Plaintext);
+    }
+    None
+}
+
+pub fn pattern_try_from_str(as_str: &str) -> Result<Universe, PatternFromStrErr> {
+    match pattern_format_detect(as_str) {
+        Some(PatternFormat::Grid) => universe_try_from_string(
+            as_str
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with(['!', '#']))
+                .map(String::from)
+                .collect(),
+        )
+        .map_err(PatternFromStrErr::Grid),
+        Some(PatternFormat::Plaintext) => pattern_try_from_plaintext(as_str),
+        Some(PatternFormat::Life105) => pattern_try_from_life_105(as_str),
+        Some(PatternFormat::Life106) => pattern_try_from_life_106(as_str),
+        Some(PatternFormat::Rle) => {
+            rle_try_from_str(as_str).map(|rle| rle.universe).map_err(PatternFromStrErr::Rle)
+        }
+        None => Err(PatternFromStrErr::UnknownFormat(UnknownFormatErr)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InvalidCellErr, InvalidCoordinateErr, PatternFormat, PatternFromStrErr, UnknownFormatErr,
+        pattern_format_detect, pattern_to_life_105, pattern_to_life_106, pattern_to_plaintext,
+        pattern_try_from_life_105, pattern_try_from_life_106, pattern_try_from_plaintext,
+        pattern_try_from_str,
+    };
+    use crate::{
+        preset::{get_preset, get_preset_groups},
+        rle::{OutOfBoundsErr, RleFromStrErr},
+        universe::{CartesianPoint, FromStringErr, InvalidLengthErr, Universe},
+    };
+
+    #[test]
+    fn invalid_cell_err() {
+        assert_eq!(InvalidCellErr { line: 3 }.to_string(), "Line 3: must match the pattern [.O*]");
+    }
+
+    #[test]
+    fn invalid_coordinate_err() {
+        assert_eq!(
+            InvalidCoordinateErr { line: 2 }.to_string(),
+            "Line 2: must match the pattern -?[0-9]+ -?[0-9]+"
+        );
+    }
+
+    #[test]
+    fn unknown_format_err() {
+        assert_eq!(
+            UnknownFormatErr.to_string(),
+            "The pattern must be RLE, plaintext, Life 1.05, Life 1.06 or a grid of [⬜⬛]"
+        );
+    }
+
+    #[test]
+    fn pattern_plaintext() {
+        let glider = get_preset("glider");
+        assert_eq!(
+            pattern_try_from_plaintext("!Name: Glider\n!\nO.O\n.OO\n.O\n"),
+            Ok(glider.clone())
+        );
+        assert_eq!(pattern_try_from_plaintext("*.*\n.**\n.*."), Ok(glider.clone()));
+        assert_eq!(pattern_to_plaintext(&glider), "O.O\n.OO\n.O\n");
+        assert_eq!(
+            pattern_to_plaintext(&Universe::from([
+                CartesianPoint::of(0, 0),
+                CartesianPoint::of(0, 2)
+            ])),
+            "O\n.\nO\n"
+        );
+        assert_eq!(pattern_to_plaintext(&Universe::default()), "");
+        assert_eq!(
+            pattern_try_from_plaintext("!Glider\nO.O\n.Ox\n"),
+            Err(PatternFromStrErr::InvalidCell(InvalidCellErr { line: 3 }))
+        );
+    }
+
+    #[test]
+    fn pattern_life_105() {
+        let glider = get_preset("glider");
+        assert_eq!(pattern_to_life_105(&glider), "#Life 1.05\n#P -1 -1\n*.*\n.**\n.*\n");
+        assert_eq!(pattern_try_from_life_105(&pattern_to_life_105(&glider)), Ok(glider.clone()));
+        assert_eq!(
+            pattern_try_from_life_105(
+                "#Life 1.05\n#D Two blocks\n#N\n#P 0 0\n**\n#P 10 -5\n*\n*\n"
+            ),
+            Ok(Universe::from([
+                CartesianPoint::of(0, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(10, 5),
+                CartesianPoint::of(10, 4),
+            ]))
+        );
+        assert_eq!(pattern_try_from_life_105("#Life 1.05\n*.*\n.**\n.*.\n"), Ok(glider));
+        assert_eq!(pattern_to_life_105(&Universe::default()), "#Life 1.05\n");
+        assert_eq!(
+            pattern_try_from_life_105("#Life 1.05\n#P 1\n*\n"),
+            Err(PatternFromStrErr::InvalidCoordinate(InvalidCoordinateErr { line: 2 }))
+        );
+        assert_eq!(
+            pattern_try_from_life_105("#Life 1.05\n#P 1 1\n*o\n"),
+            Err(PatternFromStrErr::InvalidCell(InvalidCellErr { line: 3 }))
+        );
+    }
+
+    #[test]
+    fn pattern_life_106() {
+        let glider = get_preset("glider");
+        assert_eq!(pattern_to_life_106(&glider), "#Life 1.06\n-1 -1\n1 -1\n0 0\n1 0\n0 1\n");
+        assert_eq!(pattern_try_from_life_106(&pattern_to_life_106(&glider)), Ok(glider.clone()));
+        assert_eq!(pattern_try_from_life_106("-1 -1\n1 -1\n0 0\n1 0\n0 1"), Ok(glider));
+        assert_eq!(pattern_to_life_106(&Universe::default()), "#Life 1.06\n");
+        assert_eq!(
+            pattern_try_from_life_106("#Life 1.06\n0 0\n0 a\n"),
+            Err(PatternFromStrErr::InvalidCoordinate(InvalidCoordinateErr { line: 3 }))
+        );
+        assert_eq!(
+            pattern_try_from_life_106("#Life 1.06\n0 0 0\n"),
+            Err(PatternFromStrErr::InvalidCoordinate(InvalidCoordinateErr { line: 2 }))
+        );
+    }
+
+    #[test]
+    fn test_pattern_format_detect() {
+        assert_eq!(pattern_format_detect("#Life 1.06\n0 0\n"), Some(PatternFormat::Life106));
+        assert_eq!(pattern_format_detect("0 0\n1 0\n"), Some(PatternFormat::Life106));
+        assert_eq!(pattern_format_detect("#Life 1.05\n#P 0 0\n*\n"), Some(PatternFormat::Life105));
+        assert_eq!(pattern_format_detect("!Name: Block\nOO\nOO\n"), Some(PatternFormat::Plaintext));
+        assert_eq!(pattern_format_detect("\n.O\nO.\n"), Some(PatternFormat::Plaintext));
+        assert_eq!(
+            pattern_format_detect("#N Block\nx = 2, y = 2\n2o$2o!"),
+            Some(PatternFormat::Rle)
+        );
+        assert_eq!(pattern_format_detect("⬜⬛\n⬛⬜\n"), Some(PatternFormat::Grid));
+        assert_eq!(
+            pattern_format_detect("#C ⬜⬛\nx = 2, y = 2\n2o$2o!"),
+            Some(PatternFormat::Rle)
+        );
+        assert_eq!(pattern_format_detect("#D ⬜⬛\n.O\nO.\n"), Some(PatternFormat::Plaintext));
+        assert_eq!(pattern_format_detect("#C ⬜⬛\n0 0\n1 0\n"), Some(PatternFormat::Life106));
+        assert_eq!(pattern_format_detect("!Name: ⬜⬛\nOO\nOO\n"), Some(PatternFormat::Plaintext));
+        assert_eq!(pattern_format_detect("hello"), None);
+        assert_eq!(pattern_format_detect(""), None);
+    }
+
+    #[test]
+    fn test_pattern_try_from_str() {
+        let glider = get_preset("glider");
+        for as_str in [
+            "⬛⬛⬛⬛⬛\n⬛⬜⬛⬜⬛\n⬛⬛⬜⬜⬛\n⬛⬛⬜⬛⬛\n⬛⬛⬛⬛⬛\n",
+            "#C Glider\n⬛⬛⬛⬛⬛\n⬛⬜⬛⬜⬛\n⬛⬛⬜⬜⬛\n⬛⬛⬜⬛⬛\n⬛⬛⬛⬛⬛\n",
+            "!Glider\nO.O\n.OO\n.O\n",
+            "#Life 1.05\n#P -1 -1\n*.*\n.**\n.*\n",
+            "#Life 1.06\n-1 -1\n1 -1\n0 0\n1 0\n0 1\n",
+            "#C Glider\nx = 3, y = 3, rule = B3/S23\nobo$b2o$bo!\n",
+        ] {
+            assert_eq!(pattern_try_from_str(as_str), Ok(glider.clone()));
+        }
+        assert_eq!(
+            pattern_try_from_str("⬛⬜\n⬛"),
+            Err(PatternFromStrErr::Grid(FromStringErr::InvalidLength(InvalidLengthErr)))
+        );
+        assert_eq!(
+            pattern_try_from_str("x = 1, y = 1\n2o!"),
+            Err(PatternFromStrErr::Rle(RleFromStrErr::OutOfBounds(OutOfBoundsErr {
+                line: 2,
+                column: 2,
+                width: 1,
+                height: 1
+            })))
+        );
+        assert_eq!(
+            pattern_try_from_str("hello"),
+            Err(PatternFromStrErr::UnknownFormat(UnknownFormatErr))
+        );
+    }
+
+    #[test]
+    fn pattern_round_trip_presets() {
+        for group in get_preset_groups() {
+            for sub_group in group.sub_groups {
+                for preset in sub_group.items {
+                    let universe = get_preset(&preset.id);
+                    let life_105 = pattern_to_life_105(&universe);
+                    let life_106 = pattern_to_life_106(&universe);
+                    let plaintext = pattern_to_plaintext(&universe);
+                    assert_eq!(pattern_try_from_str(&life_105), Ok(universe.clone()));
+                    assert_eq!(pattern_try_from_str(&life_106), Ok(universe.clone()));
+                    let from_plaintext = pattern_try_from_str(&plaintext).unwrap();
+                    assert_eq!(pattern_to_plaintext(&from_plaintext), plaintext);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::universe::{Universe, universe_from_str};
+use crate::{
+    analysis::PatternClass,
+    universe::{Universe, universe_from_str},
+};
 use std::{collections::HashMap, sync::LazyLock};
 
 #[derive(Clone)]
@@ -12,6 +15,9 @@ pub struct Preset {
     pub name: String,
     pub id: String,
     pub discover: PresetDiscover,
+    pub class: PatternClass,
+    pub period: Option<u64>,
+    pub speed: Option<String>,
 }
 
 #[derive(Clone)]
@@ -175,6 +181,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                         name: "Boat".into(),
                         id: "boat".into(),
                         discover: PresetDiscover { name: "JHC group".into(), year: 1970 },
+                        class: PatternClass::StillLife,
+                        period: Some(1),
+                        speed: None,
                     }],
                 },
                 PresetSubGroup { name: "Loaf".into(), id: "loaf".into(), items: vec![] },
@@ -185,6 +194,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                         name: "Block".into(),
                         id: "block".into(),
                         discover: PresetDiscover { name: "John Conway".into(), year: 1969 },
+                        class: PatternClass::StillLife,
+                        period: Some(1),
+                        speed: None,
                     }],
                 },
             ],
@@ -198,6 +210,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                     name: "Blinker".into(),
                     id: "blinker".into(),
                     discover: PresetDiscover { name: "John Conway".into(), year: 1969 },
+                    class: PatternClass::Oscillator,
+                    period: Some(2),
+                    speed: None,
                 }],
             }],
         },
@@ -210,6 +225,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                     name: "R-Pentomino".into(),
                     id: "r_pentomino".into(),
                     discover: PresetDiscover { name: "John Conway".into(), year: 1969 },
+                    class: PatternClass::Methuselah,
+                    period: None,
+                    speed: None,
                 }],
             }],
         },
@@ -222,6 +240,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                     name: "Glider".into(),
                     id: "glider".into(),
                     discover: PresetDiscover { name: "Richard K. Guy".into(), year: 1969 },
+                    class: PatternClass::Spaceship,
+                    period: Some(4),
+                    speed: Some("c/4 diagonal".into()),
                 }],
             }],
         },
@@ -234,6 +255,9 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                     name: "Gosper glider gun".into(),
                     id: "gosper_glider_gun".into(),
                     discover: PresetDiscover { name: "Bill Gosper".into(), year: 1970 },
+                    class: PatternClass::Gun,
+                    period: Some(30),
+                    speed: Some("c/4 diagonal".into()),
                 }],
             }],
         },
@@ -246,8 +270,81 @@ pub fn get_preset_groups() -> Vec<PresetGroup> {
                     name: "Puffer 1".into(),
                     id: "puffer1".into(),
                     discover: PresetDiscover { name: "Bill Gosper".into(), year: 1971 },
+                    class: PatternClass::Puffer,
+                    period: Some(128),
+                    speed: Some("c/2 orthogonal".into()),
                 }],
             }],
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_preset, get_preset_groups};
+    use crate::{
+        analysis::{Classification, ClassifySettings, PatternClass, analysis_classify},
+        rule::Rule,
+    };
+
+    fn group_class(id: &str) -> Option<PatternClass> {
+        match id {
+            "stillLife" => Some(PatternClass::StillLife),
+            "oscillators" => Some(PatternClass::Oscillator),
+            "methuselahs" => Some(PatternClass::Methuselah),
+            "spaceships" => Some(PatternClass::Spaceship),
+            "gliderGun" => Some(PatternClass::Gun),
+            "puffer" => Some(PatternClass::Puffer),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn preset_class_matches_group() {
+        for group in get_preset_groups() {
+            for sub_group in group.sub_groups {
+                for item in sub_group.items {
+                    assert_eq!(Some(item.class), group_class(&group.info.id), "{}", item.id);
+                }
+            }
+        }
+    }
+
+    fn assert_evolution_matches_class(
+        max_generations: u64,
+        include: impl Fn(&PatternClass) -> bool,
+    ) {
+        for group in get_preset_groups() {
+            for sub_group in group.sub_groups {
+                for item in sub_group.items.into_iter().filter(|item| include(&item.class)) {
+                    assert_eq!(
+                        analysis_classify(
+                            &get_preset(&item.id),
+                            &Rule::default(),
+                            max_generations,
+                            &ClassifySettings::default()
+                        ),
+                        Some(Classification {
+                            class: item.class,
+                            period: item.period,
+                            speed: item.speed
+                        }),
+                        "{}",
+                        item.id
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn preset_evolution_matches_class() {
+        assert_evolution_matches_class(200, |class| *class != PatternClass::Methuselah);
+    }
+
+    #[test]
+    #[ignore = "the methuselahs take over 1000 generations to settle"]
+    fn preset_evolution_matches_class_methuselahs() {
+        assert_evolution_matches_class(1300, |class| *class == PatternClass::Methuselah);
+    }
+}
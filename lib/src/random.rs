@@ -0,0 +1,44 @@
+#[derive(Debug, PartialEq, Clone)]
+pub struct Random {
+    pub state: u64,
+}
+
+pub fn random_from_seed(seed: u64) -> Random {
+    Random { state: seed }
+}
+
+pub fn random_next_u64(random: &mut Random) -> u64 {
+    random.state = random.state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = random.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+pub fn random_next_f64(random: &mut Random) -> f64 {
+    (random_next_u64(random) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_from_seed, random_next_f64, random_next_u64};
+
+    #[test]
+    fn test_random_next_u64() {
+        let mut random = random_from_seed(0);
+        assert_eq!(random_next_u64(&mut random), 0xe220a8397b1dcdaf);
+        assert_eq!(random_next_u64(&mut random), 0x6e789e6aa1b965f4);
+        assert_eq!(random_next_u64(&mut random), 0x06c45d188009454f);
+    }
+
+    #[test]
+    fn test_random_next_f64() {
+        let mut a = random_from_seed(42);
+        let mut b = random_from_seed(42);
+        for _ in 0..1000 {
+            let value = random_next_f64(&mut a);
+            assert!((0.0..1.0).contains(&value));
+            assert_eq!(value, random_next_f64(&mut b));
+        }
+    }
+}
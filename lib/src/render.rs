@@ -1,4 +1,10 @@
-use crate::{cell::State, render_settings::RenderSettings, universe::Universe};
+use crate::{
+    cell::State,
+    heat::{Heat, HeatMetric, heat_cell_value},
+    render_settings::RenderSettings,
+    topology::topology_bounds,
+    universe::{CartesianPoint, Universe, universe_get_age},
+};
 use manfredo::{
     cartesian::rect::{rect_f64, rect_i32},
     transform::cartesian_in_cam_to_matrix::point_i32::cartesian_in_cam_to_matrix,
@@ -6,42 +12,124 @@ use manfredo::{
 
 pub type Renderable = manfredo::cartesian::rect::rect_f64::Rect;
 
-pub fn get_values_to_render(universe: &Universe, settings: &RenderSettings) -> Vec<Renderable> {
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValueToRender {
+    pub rect: Renderable,
+    pub state: State,
+    pub age: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HeatToRender {
+    pub rect: Renderable,
+    pub value: f64,
+    pub intensity: f64,
+}
+
+fn cell_rect(point: &CartesianPoint, settings: &RenderSettings) -> Renderable {
     let dim = f64::from(settings.dim);
     let len = rect_i32::max_len(&settings.cam) as f64;
     let cell_size = dim / len;
-    let mut values_to_render: Vec<rect_f64::Rect> = universe
+    let arr_index = cartesian_in_cam_to_matrix(point, &settings.cam);
+    let gap = f64::from(settings.gap);
+    let col = arr_index.col as f64;
+    let row = arr_index.row as f64;
+    rect_f64::Rect::of(
+        col * cell_size + gap,
+        row * cell_size + gap,
+        col * cell_size + cell_size - gap,
+        row * cell_size + cell_size - gap,
+    )
+}
+
+fn sort_by_rect<T>(values: &mut [T], rect: impl Fn(&T) -> &Renderable) {
+    values.sort_by(|a, b| {
+        rect(a).min.y.partial_cmp(&rect(b).min.y).unwrap_or(std::cmp::Ordering::Greater)
+    });
+    values.sort_by(|a, b| {
+        rect(a).min.x.partial_cmp(&rect(b).min.x).unwrap_or(std::cmp::Ordering::Greater)
+    });
+}
+
+pub fn get_values_to_render(universe: &Universe, settings: &RenderSettings) -> Vec<ValueToRender> {
+    let mut values_to_render: Vec<ValueToRender> = universe
         .value
         .iter()
         .filter(|(point, _)| rect_i32::contains_point(&settings.cam, point))
-        .filter(|(_, state)| state == &&State::Alive)
-        .map(|(point, _)| {
-            let arr_index = cartesian_in_cam_to_matrix(point, &settings.cam);
-            let gap = f64::from(settings.gap);
-            let col = arr_index.col as f64;
-            let row = arr_index.row as f64;
-            rect_f64::Rect::of(
-                col * cell_size + gap,
-                row * cell_size + gap,
-                col * cell_size + cell_size - gap,
-                row * cell_size + cell_size - gap,
-            )
+        .filter(|(_, state)| **state != universe.background)
+        .map(|(point, state)| ValueToRender {
+            rect: cell_rect(point, settings),
+            state: *state,
+            age: universe_get_age(universe, point),
         })
         .collect();
-    values_to_render
-        .sort_by(|a, b| a.min.y.partial_cmp(&b.min.y).unwrap_or(std::cmp::Ordering::Greater));
-    values_to_render
-        .sort_by(|a, b| a.min.x.partial_cmp(&b.min.x).unwrap_or(std::cmp::Ordering::Greater));
+    sort_by_rect(&mut values_to_render, |value| &value.rect);
     values_to_render
 }
 
+pub fn get_heat_to_render(
+    heat: &Heat,
+    metric: HeatMetric,
+    settings: &RenderSettings,
+) -> Vec<HeatToRender> {
+    let cells: Vec<(&CartesianPoint, f64)> = heat
+        .cells
+        .iter()
+        .filter(|(point, _)| rect_i32::contains_point(&settings.cam, point))
+        .map(|(point, cell)| (point, heat_cell_value(cell, metric)))
+        .filter(|(_, value)| *value > 0.0)
+        .collect();
+    let max = cells.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    let mut heat_to_render: Vec<HeatToRender> = cells
+        .into_iter()
+        .map(|(point, value)| HeatToRender {
+            rect: cell_rect(point, settings),
+            value,
+            intensity: value / max,
+        })
+        .collect();
+    sort_by_rect(&mut heat_to_render, |value| &value.rect);
+    heat_to_render
+}
+
+pub fn get_boundary_to_render(
+    universe: &Universe,
+    settings: &RenderSettings,
+) -> Option<Renderable> {
+    let bounds = topology_bounds(&universe.topology)?;
+    let dim = f64::from(settings.dim);
+    let len = rect_i32::max_len(&settings.cam) as f64;
+    let cell_size = dim / len;
+    let min_col = f64::from(bounds.min.x - settings.cam.min.x);
+    let min_row = f64::from(settings.cam.max.y - bounds.max.y);
+    let max_col = f64::from(bounds.max.x - settings.cam.min.x + 1);
+    let max_row = f64::from(settings.cam.max.y - bounds.min.y + 1);
+    Some(rect_f64::Rect::of(
+        min_col * cell_size,
+        min_row * cell_size,
+        max_col * cell_size,
+        max_row * cell_size,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Renderable, get_values_to_render};
+    use super::{
+        HeatToRender, Renderable, ValueToRender, get_boundary_to_render, get_heat_to_render,
+        get_values_to_render,
+    };
     use crate::{
+        cell::State,
+        heat::{Heat, HeatCell, HeatMetric},
         render_settings::{Cam, RenderSettings},
-        universe::{Universe, universe_from_str},
+        topology::{Bounds, Topology},
+        universe::{CartesianPoint, Universe, universe_from_str, universe_track_ages},
     };
+    use std::collections::HashMap;
+
+    fn alive(rect: Renderable) -> ValueToRender {
+        ValueToRender { rect, state: State::Alive, age: None }
+    }
 
     fn get_universe() -> Universe {
         universe_from_str([
@@ -65,14 +153,14 @@ mod tests {
         assert_eq!(
             get_values_to_render(&universe, &s),
             [
-                Renderable::of(0.0, 0.0, 100.0, 100.0),
-                Renderable::of(0.0, 900.0, 100.0, 1000.0),
-                Renderable::of(100.0, 100.0, 200.0, 200.0),
-                Renderable::of(100.0, 800.0, 200.0, 900.0),
-                Renderable::of(800.0, 100.0, 900.0, 200.0),
-                Renderable::of(800.0, 800.0, 900.0, 900.0),
-                Renderable::of(900.0, 0.0, 1000.0, 100.0),
-                Renderable::of(900.0, 900.0, 1000.0, 1000.0),
+                alive(Renderable::of(0.0, 0.0, 100.0, 100.0)),
+                alive(Renderable::of(0.0, 900.0, 100.0, 1000.0)),
+                alive(Renderable::of(100.0, 100.0, 200.0, 200.0)),
+                alive(Renderable::of(100.0, 800.0, 200.0, 900.0)),
+                alive(Renderable::of(800.0, 100.0, 900.0, 200.0)),
+                alive(Renderable::of(800.0, 800.0, 900.0, 900.0)),
+                alive(Renderable::of(900.0, 0.0, 1000.0, 100.0)),
+                alive(Renderable::of(900.0, 900.0, 1000.0, 1000.0)),
             ]
         );
     }
@@ -85,27 +173,27 @@ mod tests {
         assert_eq!(
             get_values_to_render(&universe, &s_gap1),
             [
-                Renderable::of(1.0, 1.0, 99.0, 99.0),
-                Renderable::of(1.0, 901.0, 99.0, 999.0),
-                Renderable::of(101.0, 101.0, 199.0, 199.0),
-                Renderable::of(101.0, 801.0, 199.0, 899.0),
-                Renderable::of(801.0, 101.0, 899.0, 199.0),
-                Renderable::of(801.0, 801.0, 899.0, 899.0),
-                Renderable::of(901.0, 1.0, 999.0, 99.0),
-                Renderable::of(901.0, 901.0, 999.0, 999.0),
+                alive(Renderable::of(1.0, 1.0, 99.0, 99.0)),
+                alive(Renderable::of(1.0, 901.0, 99.0, 999.0)),
+                alive(Renderable::of(101.0, 101.0, 199.0, 199.0)),
+                alive(Renderable::of(101.0, 801.0, 199.0, 899.0)),
+                alive(Renderable::of(801.0, 101.0, 899.0, 199.0)),
+                alive(Renderable::of(801.0, 801.0, 899.0, 899.0)),
+                alive(Renderable::of(901.0, 1.0, 999.0, 99.0)),
+                alive(Renderable::of(901.0, 901.0, 999.0, 999.0)),
             ]
         );
         assert_eq!(
             get_values_to_render(&universe, &s_gap2),
             [
-                Renderable::of(2.0, 2.0, 98.0, 98.0),
-                Renderable::of(2.0, 902.0, 98.0, 998.0),
-                Renderable::of(102.0, 102.0, 198.0, 198.0),
-                Renderable::of(102.0, 802.0, 198.0, 898.0),
-                Renderable::of(802.0, 102.0, 898.0, 198.0),
-                Renderable::of(802.0, 802.0, 898.0, 898.0),
-                Renderable::of(902.0, 2.0, 998.0, 98.0),
-                Renderable::of(902.0, 902.0, 998.0, 998.0),
+                alive(Renderable::of(2.0, 2.0, 98.0, 98.0)),
+                alive(Renderable::of(2.0, 902.0, 98.0, 998.0)),
+                alive(Renderable::of(102.0, 102.0, 198.0, 198.0)),
+                alive(Renderable::of(102.0, 802.0, 198.0, 898.0)),
+                alive(Renderable::of(802.0, 102.0, 898.0, 198.0)),
+                alive(Renderable::of(802.0, 802.0, 898.0, 898.0)),
+                alive(Renderable::of(902.0, 2.0, 998.0, 98.0)),
+                alive(Renderable::of(902.0, 902.0, 998.0, 998.0)),
             ]
         );
     }
@@ -118,23 +206,23 @@ mod tests {
         assert_eq!(
             get_values_to_render(&universe, &s_cam_minus1),
             [
-                Renderable::of(100.0, 0.0, 200.0, 100.0),
-                Renderable::of(100.0, 900.0, 200.0, 1000.0),
-                Renderable::of(200.0, 100.0, 300.0, 200.0),
-                Renderable::of(200.0, 800.0, 300.0, 900.0),
-                Renderable::of(900.0, 100.0, 1000.0, 200.0),
-                Renderable::of(900.0, 800.0, 1000.0, 900.0),
+                alive(Renderable::of(100.0, 0.0, 200.0, 100.0)),
+                alive(Renderable::of(100.0, 900.0, 200.0, 1000.0)),
+                alive(Renderable::of(200.0, 100.0, 300.0, 200.0)),
+                alive(Renderable::of(200.0, 800.0, 300.0, 900.0)),
+                alive(Renderable::of(900.0, 100.0, 1000.0, 200.0)),
+                alive(Renderable::of(900.0, 800.0, 1000.0, 900.0)),
             ]
         );
         assert_eq!(
             get_values_to_render(&universe, &s_cam_plus1),
             [
-                Renderable::of(0.0, 100.0, 100.0, 200.0),
-                Renderable::of(0.0, 800.0, 100.0, 900.0),
-                Renderable::of(700.0, 100.0, 800.0, 200.0),
-                Renderable::of(700.0, 800.0, 800.0, 900.0),
-                Renderable::of(800.0, 0.0, 900.0, 100.0),
-                Renderable::of(800.0, 900.0, 900.0, 1000.0),
+                alive(Renderable::of(0.0, 100.0, 100.0, 200.0)),
+                alive(Renderable::of(0.0, 800.0, 100.0, 900.0)),
+                alive(Renderable::of(700.0, 100.0, 800.0, 200.0)),
+                alive(Renderable::of(700.0, 800.0, 800.0, 900.0)),
+                alive(Renderable::of(800.0, 0.0, 900.0, 100.0)),
+                alive(Renderable::of(800.0, 900.0, 900.0, 1000.0)),
             ]
         );
     }
@@ -146,15 +234,137 @@ mod tests {
         assert_eq!(
             get_values_to_render(&universe, &s),
             [
-                Renderable::of(0.0, 0.0, 99.6, 99.6),
-                Renderable::of(0.0, 896.4, 99.6, 996.0),
-                Renderable::of(99.6, 99.6, 199.2, 199.2),
-                Renderable::of(99.6, 796.8, 199.2, 896.4),
-                Renderable::of(796.8, 99.6, 896.4, 199.2),
-                Renderable::of(796.8, 796.8, 896.4, 896.4),
-                Renderable::of(896.4, 0.0, 996.0, 99.6),
-                Renderable::of(896.4, 896.4, 996.0, 996.0)
+                alive(Renderable::of(0.0, 0.0, 99.6, 99.6)),
+                alive(Renderable::of(0.0, 896.4, 99.6, 996.0)),
+                alive(Renderable::of(99.6, 99.6, 199.2, 199.2)),
+                alive(Renderable::of(99.6, 796.8, 199.2, 896.4)),
+                alive(Renderable::of(796.8, 99.6, 896.4, 199.2)),
+                alive(Renderable::of(796.8, 796.8, 896.4, 896.4)),
+                alive(Renderable::of(896.4, 0.0, 996.0, 99.6)),
+                alive(Renderable::of(896.4, 896.4, 996.0, 996.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn render_states() {
+        let universe = Universe::from(HashMap::from([
+            (CartesianPoint::of(-1, 0), State::Alive),
+            (CartesianPoint::of(0, 0), State::Dying(2)),
+            (CartesianPoint::of(1, 0), State::Dying(3)),
+            (CartesianPoint::of(0, 1), State::Dead),
+        ]));
+        let s = RenderSettings { cam: Cam::of(-2, -2, 1, 1), dim: 100, gap: 0 };
+        assert_eq!(
+            get_values_to_render(&universe, &s),
+            [
+                ValueToRender {
+                    rect: Renderable::of(25.0, 25.0, 50.0, 50.0),
+                    state: State::Alive,
+                    age: None
+                },
+                ValueToRender {
+                    rect: Renderable::of(50.0, 25.0, 75.0, 50.0),
+                    state: State::Dying(2),
+                    age: None
+                },
+                ValueToRender {
+                    rect: Renderable::of(75.0, 25.0, 100.0, 50.0),
+                    state: State::Dying(3),
+                    age: None
+                },
             ]
         );
     }
+
+    #[test]
+    fn render_background() {
+        let universe = Universe {
+            value: HashMap::from([
+                (CartesianPoint::of(-1, 0), State::Dead),
+                (CartesianPoint::of(0, 0), State::Alive),
+            ]),
+            background: State::Alive,
+            ..Default::default()
+        };
+        let s = RenderSettings { cam: Cam::of(-2, -2, 1, 1), dim: 100, gap: 0 };
+        assert_eq!(
+            get_values_to_render(&universe, &s),
+            [ValueToRender {
+                rect: Renderable::of(25.0, 25.0, 50.0, 50.0),
+                state: State::Dead,
+                age: None
+            }]
+        );
+    }
+
+    #[test]
+    fn render_ages() {
+        let mut universe = Universe::from([CartesianPoint::of(0, 0)]);
+        let s = RenderSettings { cam: Cam::of(-2, -2, 1, 1), dim: 100, gap: 0 };
+        universe_track_ages(&mut universe, true);
+        universe.ages = Some(HashMap::from([(CartesianPoint::of(0, 0), 7)]));
+        assert_eq!(
+            get_values_to_render(&universe, &s),
+            [ValueToRender {
+                rect: Renderable::of(50.0, 25.0, 75.0, 50.0),
+                state: State::Alive,
+                age: Some(7)
+            }]
+        );
+    }
+
+    #[test]
+    fn render_heat() {
+        let heat = Heat {
+            cells: HashMap::from([
+                (CartesianPoint::of(0, 0), HeatCell { alive: 4.0, changes: 0.0 }),
+                (CartesianPoint::of(1, 0), HeatCell { alive: 2.0, changes: 4.0 }),
+                (CartesianPoint::of(2, 0), HeatCell { alive: 8.0, changes: 8.0 }),
+            ]),
+            ..Default::default()
+        };
+        let s = RenderSettings { cam: Cam::of(-2, -2, 1, 1), dim: 100, gap: 0 };
+        assert_eq!(
+            get_heat_to_render(&heat, HeatMetric::Alive, &s),
+            [
+                HeatToRender {
+                    rect: Renderable::of(50.0, 25.0, 75.0, 50.0),
+                    value: 4.0,
+                    intensity: 1.0
+                },
+                HeatToRender {
+                    rect: Renderable::of(75.0, 25.0, 100.0, 50.0),
+                    value: 2.0,
+                    intensity: 0.5
+                },
+            ]
+        );
+        assert_eq!(
+            get_heat_to_render(&heat, HeatMetric::Changes, &s),
+            [HeatToRender {
+                rect: Renderable::of(75.0, 25.0, 100.0, 50.0),
+                value: 4.0,
+                intensity: 1.0
+            }]
+        );
+        assert_eq!(get_heat_to_render(&Heat::default(), HeatMetric::Alive, &s), []);
+    }
+
+    #[test]
+    fn render_boundary() {
+        let mut universe = Universe::default();
+        let s = RenderSettings { cam: Cam::of(-2, -2, 1, 1), dim: 100, gap: 1 };
+        assert_eq!(get_boundary_to_render(&universe, &s), None);
+        universe.topology = Topology::Torus(Bounds::of(-1, -1, 0, 0));
+        assert_eq!(
+            get_boundary_to_render(&universe, &s),
+            Some(Renderable::of(25.0, 25.0, 75.0, 75.0))
+        );
+        universe.topology = Topology::Bounded(Bounds::of(-2, -1, 3, 1));
+        assert_eq!(
+            get_boundary_to_render(&universe, &s),
+            Some(Renderable::of(0.0, 0.0, 150.0, 75.0))
+        );
+    }
 }
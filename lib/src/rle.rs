@@ -0,0 +1,648 @@
+use crate::{
+    cell::{State, cell_index, cell_of_index},
+    rule::{Rule, RuleFromStrErr, rule_try_from_str},
+    universe::{CartesianPoint, MAX_REGION_LEN, Universe, universe_bounds},
+};
+use std::fmt;
+
+const RLE_LINE_LEN: usize = 70;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Rle {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    pub universe: Universe,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MissingHeaderErr;
+
+impl fmt::Display for MissingHeaderErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The pattern must declare a header x = [0-9]+, y = [0-9]+(, rule = .+)?")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidHeaderErr {
+    pub line: usize,
+}
+
+impl fmt::Display for InvalidHeaderErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {}: the header must match the pattern x = [0-9]+, y = [0-9]+(, rule = .+)?",
+            self.line
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidPositionErr {
+    pub line: usize,
+}
+
+impl fmt::Display for InvalidPositionErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: the position must match the pattern #[PR] -?[0-9]+ -?[0-9]+", self.line)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SizeTooLargeErr {
+    pub line: usize,
+}
+
+impl fmt::Display for SizeTooLargeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: the declared size must have at most {MAX_REGION_LEN} cells", self.line)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PositionOutOfRangeErr {
+    pub line: usize,
+}
+
+impl fmt::Display for PositionOutOfRangeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: the pattern must stay within the coordinate range", self.line)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidCharacterErr {
+    pub line: usize,
+    pub column: usize,
+    pub character: char,
+}
+
+impl fmt::Display for InvalidCharacterErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: '{}' must match the pattern [0-9bo.A-Xp-y$!]",
+            self.line, self.column, self.character
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OutOfBoundsErr {
+    pub line: usize,
+    pub column: usize,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl fmt::Display for OutOfBoundsErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: the pattern exceeds the declared size of {} by {}",
+            self.line, self.column, self.width, self.height
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RleFromStrErr {
+    MissingHeader(MissingHeaderErr),
+    InvalidHeader(InvalidHeaderErr),
+    InvalidPosition(InvalidPositionErr),
+    SizeTooLarge(SizeTooLargeErr),
+    PositionOutOfRange(PositionOutOfRangeErr),
+    InvalidRule(RuleFromStrErr),
+    InvalidCharacter(InvalidCharacterErr),
+    OutOfBounds(OutOfBoundsErr),
+}
+
+impl fmt::Display for RleFromStrErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RleFromStrErr::MissingHeader(err) => err.fmt(f),
+            RleFromStrErr::InvalidHeader(err) => err.fmt(f),
+            RleFromStrErr::InvalidPosition(err) => err.fmt(f),
+            RleFromStrErr::SizeTooLarge(err) => err.fmt(f),
+            RleFromStrErr::PositionOutOfRange(err) => err.fmt(f),
+            RleFromStrErr::InvalidRule(err) => err.fmt(f),
+            RleFromStrErr::InvalidCharacter(err) => err.fmt(f),
+            RleFromStrErr::OutOfBounds(err) => err.fmt(f),
+        }
+    }
+}
+
+struct RleHeader {
+    width: u32,
+    height: u32,
+    rule: Option<Rule>,
+}
+
+fn header_try_from_str(line: &str, number: usize) -> Result<RleHeader, RleFromStrErr> {
+    let invalid = || RleFromStrErr::InvalidHeader(InvalidHeaderErr { line: number });
+    let (size, rule) = match line.find("rule") {
+        Some(index) => (line[..index].trim_end().trim_end_matches(','), Some(&line[index..])),
+        None => (line, None),
+    };
+    let rule = match rule {
+        Some(rule) => {
+            let (_, value) = rule.split_once('=').ok_or_else(invalid)?;
+            Some(rule_try_from_str(value).map_err(RleFromStrErr::InvalidRule)?)
+        }
+        None => None,
+    };
+    let mut width = None;
+    let mut height = None;
+    for part in size.split(',') {
+        let (key, value) = part.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim().parse::<u32>().map_err(|_| invalid())?;
+        match key.trim() {
+            "x" => width = Some(value),
+            "y" => height = Some(value),
+            _ => return Err(invalid()),
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) if u64::from(width) * u64::from(height) > MAX_REGION_LEN => {
+            Err(RleFromStrErr::SizeTooLarge(SizeTooLargeErr { line: number }))
+        }
+        (Some(width), Some(height)) => Ok(RleHeader { width, height, rule }),
+        _ => Err(invalid()),
+    }
+}
+
+fn position_try_from_str(line: &str, number: usize) -> Result<CartesianPoint, RleFromStrErr> {
+    let invalid = || RleFromStrErr::InvalidPosition(InvalidPositionErr { line: number });
+    let coordinates: Vec<i32> = line
+        .split_whitespace()
+        .map(|value| value.parse::<i32>().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    match coordinates[..] {
+        [x, y] => Ok(CartesianPoint::of(x, y.checked_neg().ok_or_else(invalid)?)),
+        _ => Err(invalid()),
+    }
+}
+
+fn state_letter(letter: char) -> Option<u8> {
+    matches!(letter, 'A'..='X').then(|| letter as u8 - b'A' + 1)
+}
+
+fn state_to_rle(state: &State, multi_state: bool) -> String {
+    let index = cell_index(state);
+    if !multi_state {
+        return if index == 0 { "b".into() } else { "o".into() };
+    }
+    match index {
+        0 => ".".into(),
+        1..=24 => char::from(b'A' + index - 1).into(),
+        index => {
+            let prefix = char::from(b'p' + (index - 1) / 24 - 1);
+            let letter = char::from(b'A' + (index - 1) % 24);
+            format!("{prefix}{letter}")
+        }
+    }
+}
+
+pub fn rle_try_from_str(as_str: &str) -> Result<Rle, RleFromStrErr> {
+    let mut rle = Rle::default();
+    let mut position = None;
+    let mut header = None;
+    let mut lines = as_str.lines().enumerate().map(|(index, line)| (index + 1, line));
+    for (number, line) in lines.by_ref() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let kind = chars.next();
+            let content = chars.as_str().trim();
+            match kind {
+                Some('N') => rle.name = Some(content.into()),
+                Some('O') => rle.author = Some(content.into()),
+                Some('C' | 'c') => rle.comments.push(content.into()),
+                Some('P' | 'R') => {
+                    position = Some((position_try_from_str(content, number)?, number))
+                }
+                _ => {}
+            }
+            continue;
+        }
+        header = Some(header_try_from_str(line, number)?);
+        break;
+    }
+    let RleHeader { width, height, rule } =
+        header.ok_or(RleFromStrErr::MissingHeader(MissingHeaderErr))?;
+    rle.rule = rule;
+    let origin = match position {
+        Some((origin, number)) => {
+            let fits = origin.x.checked_add_unsigned(width.saturating_sub(1)).is_some()
+                && origin.y.checked_sub_unsigned(height.saturating_sub(1)).is_some();
+            if !fits {
+                return Err(RleFromStrErr::PositionOutOfRange(PositionOutOfRangeErr {
+                    line: number,
+                }));
+            }
+            origin
+        }
+        None => {
+            let width = width as i32;
+            let height = height as i32;
+            CartesianPoint::of(-width / 2, -height / 2 + height - 1)
+        }
+    };
+    let mut row: u32 = 0;
+    let mut col: u32 = 0;
+    'body: for (number, line) in lines {
+        let mut count: Option<u32> = None;
+        let mut prefix: Option<char> = None;
+        for (index, character) in line.chars().enumerate() {
+            let column = index + 1;
+            let invalid = || {
+                RleFromStrErr::InvalidCharacter(InvalidCharacterErr {
+                    line: number,
+                    column,
+                    character,
+                })
+            };
+            let out_of_bounds = || {
+                RleFromStrErr::OutOfBounds(OutOfBoundsErr { line: number, column, width, height })
+            };
+            if prefix.is_some() && !matches!(character, 'A'..='X') {
+                return Err(invalid());
+            }
+            let state = match character {
+                '0'..='9' => {
+                    let digit = character.to_digit(10).unwrap_or_default();
+                    count = Some(
+                        count
+                            .unwrap_or_default()
+                            .checked_mul(10)
+                            .and_then(|count| count.checked_add(digit))
+                            .ok_or_else(out_of_bounds)?,
+                    );
+                    continue;
+                }
+                'p'..='y' => {
+                    prefix = Some(character);
+                    continue;
+                }
+                ' ' | '\t' => continue,
+                '!' => break 'body,
+                '$' => {
+                    row = row.saturating_add(count.take().unwrap_or(1));
+                    col = 0;
+                    continue;
+                }
+                'b' | '.' => State::Dead,
+                'o' => State::Alive,
+                'A'..='X' => {
+                    let letter = state_letter(character).unwrap_or_default();
+                    let index = match prefix.take() {
+                        Some(prefix) => (prefix as u8 - b'p' + 1)
+                            .checked_mul(24)
+                            .and_then(|base| base.checked_add(letter))
+                            .ok_or_else(invalid)?,
+                        None => letter,
+                    };
+                    cell_of_index(index)
+                }
+                _ => return Err(invalid()),
+            };
+            let run = count.take().unwrap_or(1);
+            let end = col.checked_add(run).ok_or_else(out_of_bounds)?;
+            if state != State::Dead {
+                if row >= height || end > width {
+                    return Err(out_of_bounds());
+                }
+                let y = origin.y.checked_sub_unsigned(row).ok_or_else(out_of_bounds)?;
+                for offset in col..end {
+                    let x = origin.x.checked_add_unsigned(offset).ok_or_else(out_of_bounds)?;
+                    rle.universe.value.insert(CartesianPoint::of(x, y), state);
+                }
+            }
+            col = end;
+        }
+    }
+    Ok(rle)
+}
+
+pub fn rle_from_str(as_str: &str) -> Rle {
+    rle_try_from_str(as_str).unwrap()
+}
+
+pub fn rle_to_string(rle: &Rle) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(name) = &rle.name {
+        lines.push(format!("#N {name}"));
+    }
+    if let Some(author) = &rle.author {
+        lines.push(format!("#O {author}"));
+    }
+    for comment in &rle.comments {
+        lines.push(format!("#C {comment}"));
+    }
+    let bounds = universe_bounds(&rle.universe);
+    if let Some(bounds) = &bounds {
+        lines.push(format!("#R {} {}", bounds.min.x, -bounds.max.y));
+    }
+    let (width, height) = bounds.as_ref().map_or((0, 0), |bounds| {
+        (bounds.max.x - bounds.min.x + 1, bounds.max.y - bounds.min.y + 1)
+    });
+    lines.push(match &rle.rule {
+        Some(rule) => format!("x = {width}, y = {height}, rule = {rule}"),
+        None => format!("x = {width}, y = {height}"),
+    });
+    let multi_state = rle.universe.value.values().any(|state| cell_index(state) > 1);
+    let mut tokens: Vec<String> = Vec::new();
+    let mut push = |count: u32, token: String| {
+        tokens.push(if count > 1 { format!("{count}{token}") } else { token });
+    };
+    if let Some(bounds) = &bounds {
+        let mut empty_rows: u32 = 0;
+        for y in (bounds.min.y..=bounds.max.y).rev() {
+            let mut runs: Vec<(u32, State)> = Vec::new();
+            for x in bounds.min.x..=bounds.max.x {
+                let state =
+                    rle.universe.value.get(&CartesianPoint::of(x, y)).copied().unwrap_or_default();
+                match runs.last_mut() {
+                    Some((count, last)) if *last == state => *count += 1,
+                    _ => runs.push((1, state)),
+                }
+            }
+            if runs.last().is_some_and(|(_, state)| *state == State::Dead) {
+                runs.pop();
+            }
+            if y != bounds.max.y {
+                empty_rows += 1;
+            }
+            if runs.is_empty() {
+                continue;
+            }
+            if empty_rows > 0 {
+                push(empty_rows, "$".into());
+                empty_rows = 0;
+            }
+            for (count, state) in runs {
+                push(count, state_to_rle(&state, multi_state));
+            }
+        }
+    }
+    push(1, "!".into());
+    let mut body = String::new();
+    for token in tokens {
+        if body.len() + token.len() > RLE_LINE_LEN {
+            lines.push(std::mem::take(&mut body));
+        }
+        body.push_str(&token);
+    }
+    lines.push(body);
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InvalidCharacterErr, InvalidHeaderErr, InvalidPositionErr, MissingHeaderErr,
+        OutOfBoundsErr, PositionOutOfRangeErr, Rle, RleFromStrErr, SizeTooLargeErr, rle_from_str,
+        rle_to_string, rle_try_from_str,
+    };
+    use crate::{
+        cell::State,
+        preset::{get_preset, get_preset_groups},
+        rule::{InvalidNeighborCountErr, Rule, RuleFromStrErr, rule_from_str},
+        universe::{CartesianPoint, Universe},
+    };
+
+    const GLIDER: &str = "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\nx = 3, y = 3, rule = B3/S23\nobo$b2o$bo!\n";
+
+    #[test]
+    fn missing_header_err() {
+        assert_eq!(
+            MissingHeaderErr.to_string(),
+            "The pattern must declare a header x = [0-9]+, y = [0-9]+(, rule = .+)?"
+        );
+    }
+
+    #[test]
+    fn invalid_header_err() {
+        assert_eq!(
+            InvalidHeaderErr { line: 2 }.to_string(),
+            "Line 2: the header must match the pattern x = [0-9]+, y = [0-9]+(, rule = .+)?"
+        );
+    }
+
+    #[test]
+    fn size_too_large_err() {
+        assert_eq!(
+            SizeTooLargeErr { line: 2 }.to_string(),
+            "Line 2: the declared size must have at most 4194304 cells"
+        );
+    }
+
+    #[test]
+    fn position_out_of_range_err() {
+        assert_eq!(
+            PositionOutOfRangeErr { line: 1 }.to_string(),
+            "Line 1: the pattern must stay within the coordinate range"
+        );
+    }
+
+    #[test]
+    fn rle_too_large() {
+        assert_eq!(
+            rle_try_from_str("x = 2000000000, y = 1\n2000000000o!"),
+            Err(RleFromStrErr::SizeTooLarge(SizeTooLargeErr { line: 1 }))
+        );
+        assert_eq!(
+            rle_try_from_str("#C big\nx = 4096, y = 1025\no!"),
+            Err(RleFromStrErr::SizeTooLarge(SizeTooLargeErr { line: 2 }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 4096, y = 1024\n4096o!").map(|rle| rle.universe.value.len()),
+            Ok(4096)
+        );
+        assert_eq!(
+            rle_try_from_str("#P 2147483647 0\nx = 2, y = 1\n2o!"),
+            Err(RleFromStrErr::PositionOutOfRange(PositionOutOfRangeErr { line: 1 }))
+        );
+        assert_eq!(
+            rle_try_from_str("#P 0 -2147483648\nx = 1, y = 1\no!"),
+            Err(RleFromStrErr::InvalidPosition(InvalidPositionErr { line: 1 }))
+        );
+        assert_eq!(
+            rle_try_from_str("#P 2147483646 0\nx = 2, y = 1\n2o!")
+                .map(|rle| rle.universe.value.len()),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn invalid_position_err() {
+        assert_eq!(
+            InvalidPositionErr { line: 1 }.to_string(),
+            "Line 1: the position must match the pattern #[PR] -?[0-9]+ -?[0-9]+"
+        );
+    }
+
+    #[test]
+    fn invalid_character_err() {
+        assert_eq!(
+            InvalidCharacterErr { line: 3, column: 4, character: 'z' }.to_string(),
+            "Line 3, column 4: 'z' must match the pattern [0-9bo.A-Xp-y$!]"
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_err() {
+        assert_eq!(
+            OutOfBoundsErr { line: 2, column: 3, width: 3, height: 1 }.to_string(),
+            "Line 2, column 3: the pattern exceeds the declared size of 3 by 1"
+        );
+    }
+
+    #[test]
+    fn rle_from_str_glider() {
+        assert_eq!(
+            rle_from_str(GLIDER),
+            Rle {
+                name: Some("Glider".into()),
+                author: Some("Richard K. Guy".into()),
+                comments: vec!["The smallest spaceship.".into()],
+                rule: Some(Rule::default()),
+                universe: get_preset("glider"),
+            }
+        );
+        assert_eq!(
+            rle_from_str("#P 10 -4\nx = 2, y = 1\n2o!").universe,
+            Universe::from([CartesianPoint::of(10, 4), CartesianPoint::of(11, 4)])
+        );
+        assert_eq!(
+            rle_from_str("x = 4, y = 3, rule = B2/S/C3\n\n2A$\n$\n2.B!"),
+            Rle {
+                rule: Some(rule_from_str("B2/S/C3")),
+                universe: Universe::from(std::collections::HashMap::from([
+                    (CartesianPoint::of(-2, 1), State::Alive),
+                    (CartesianPoint::of(-1, 1), State::Alive),
+                    (CartesianPoint::of(0, -1), State::Dying(2)),
+                ])),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            rle_from_str("x = 1, y = 1\npA!").universe.value[&CartesianPoint::of(0, 0)],
+            State::Dying(25)
+        );
+    }
+
+    #[test]
+    fn rle_try_from_str_err() {
+        assert_eq!(
+            rle_try_from_str("#C only a comment\n"),
+            Err(RleFromStrErr::MissingHeader(MissingHeaderErr))
+        );
+        assert_eq!(
+            rle_try_from_str("#N Name\nx = 3, z = 3\nbo!"),
+            Err(RleFromStrErr::InvalidHeader(InvalidHeaderErr { line: 2 }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 3\nbo!"),
+            Err(RleFromStrErr::InvalidHeader(InvalidHeaderErr { line: 1 }))
+        );
+        assert_eq!(
+            rle_try_from_str("#R 1\nx = 3, y = 3\nbo!"),
+            Err(RleFromStrErr::InvalidPosition(InvalidPositionErr { line: 1 }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 3, y = 3, rule = B9/S\nbo!"),
+            Err(RleFromStrErr::InvalidRule(RuleFromStrErr::InvalidNeighborCount(
+                InvalidNeighborCountErr
+            )))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 3, y = 3\nbo$\n2bz!"),
+            Err(RleFromStrErr::InvalidCharacter(InvalidCharacterErr {
+                line: 3,
+                column: 3,
+                character: 'z'
+            }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 1, y = 1\nzA!"),
+            Err(RleFromStrErr::InvalidCharacter(InvalidCharacterErr {
+                line: 2,
+                column: 1,
+                character: 'z'
+            }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 3, y = 1\n2b2o!"),
+            Err(RleFromStrErr::OutOfBounds(OutOfBoundsErr {
+                line: 2,
+                column: 4,
+                width: 3,
+                height: 1
+            }))
+        );
+        assert_eq!(
+            rle_try_from_str("x = 3, y = 1\no$o!"),
+            Err(RleFromStrErr::OutOfBounds(OutOfBoundsErr {
+                line: 2,
+                column: 3,
+                width: 3,
+                height: 1
+            }))
+        );
+    }
+
+    #[test]
+    fn rle_from_str_larger_than_life() {
+        assert_eq!(
+            rle_from_str("x = 1, y = 1, rule = R2,C0,M1,S9..12,B7..8,NM\no!").rule,
+            Some(rule_from_str("R2,C0,M1,S9..12,B7..8,NM"))
+        );
+    }
+
+    #[test]
+    fn rle_to_string_glider() {
+        assert_eq!(
+            rle_to_string(&rle_from_str(GLIDER)),
+            "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\n#R -1 -1\nx = 3, y = 3, rule = B3/S23\nobo$b2o$bo!\n"
+        );
+        assert_eq!(rle_to_string(&Rle::default()), "x = 0, y = 0\n!\n");
+        let universe = Universe::from(std::collections::HashMap::from([
+            (CartesianPoint::of(0, 0), State::Alive),
+            (CartesianPoint::of(1, 0), State::Dying(2)),
+            (CartesianPoint::of(3, -3), State::Dying(30)),
+        ]));
+        assert_eq!(
+            rle_to_string(&Rle { universe, ..Default::default() }),
+            "#R 0 0\nx = 4, y = 4\nAB3$3.pF!\n"
+        );
+        let line =
+            Universe::from((0..100).map(|x| CartesianPoint::of(x * 2, 0)).collect::<Vec<_>>());
+        let as_str = rle_to_string(&Rle { universe: line.clone(), ..Default::default() });
+        assert!(as_str.lines().all(|line| line.len() <= 70));
+        assert_eq!(rle_from_str(&as_str).universe, line);
+    }
+
+    #[test]
+    fn rle_round_trip_presets() {
+        for group in get_preset_groups() {
+            for sub_group in group.sub_groups {
+                for preset in sub_group.items {
+                    let rle = Rle {
+                        name: Some(preset.name),
+                        rule: Some(Rule::default()),
+                        universe: get_preset(&preset.id),
+                        ..Default::default()
+                    };
+                    assert_eq!(rle_from_str(&rle_to_string(&rle)), rle, "{}", preset.id);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,946 @@
+use crate::{
+    cell::{State, cell_iterate, cell_iterate_configuration, cell_toggle},
+    neighbor::{
+        HENSEL_LETTERS, NeighborConfiguration, Neighborhood, neighbor_configurations,
+        neighborhood_radius, neighborhood_size,
+    },
+    table::Table,
+    universe::CartesianPoint,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Isotropic {
+    pub birth: Vec<NeighborConfiguration>,
+    pub survival: Vec<NeighborConfiguration>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rule {
+    pub birth: Vec<u32>,
+    pub survival: Vec<u32>,
+    pub generations: u8,
+    pub neighborhood: Neighborhood,
+    pub middle: bool,
+    pub isotropic: Option<Isotropic>,
+    pub table: Option<Table>,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule {
+            birth: vec![3],
+            survival: vec![2, 3],
+            generations: 2,
+            neighborhood: Neighborhood::default(),
+            middle: false,
+            isotropic: None,
+            table: None,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match (&self.neighborhood, self.middle) {
+            (Neighborhood::Moore(1), false) => Some(""),
+            (Neighborhood::VonNeumann(1), false) => Some("V"),
+            (Neighborhood::Hexagonal(1), false) => Some("H"),
+            _ => None,
+        };
+        if let Some(table) = &self.table {
+            return write!(f, "{}", table.name);
+        }
+        if let Some(isotropic) = &self.isotropic {
+            write!(
+                f,
+                "B{}/S{}",
+                hensel_to_string(&isotropic.birth),
+                hensel_to_string(&isotropic.survival)
+            )?;
+            if self.generations > 2 {
+                write!(f, "/C{}", self.generations)?;
+            }
+            return Ok(());
+        }
+        if let Some(suffix) = suffix {
+            write!(f, "B{}/S{}", digits_to_string(&self.birth), digits_to_string(&self.survival))?;
+            if self.generations > 2 {
+                write!(f, "/C{}", self.generations)?;
+            }
+            return write!(f, "{suffix}");
+        }
+        let radius = neighborhood_radius(&self.neighborhood).max(1);
+        let kind = match &self.neighborhood {
+            Neighborhood::Moore(_) => "M".into(),
+            Neighborhood::VonNeumann(_) => "N".into(),
+            Neighborhood::Hexagonal(_) => "H".into(),
+            Neighborhood::Custom(offsets) => format!("@{}", mask_to_string(offsets, radius)),
+        };
+        write!(
+            f,
+            "R{},C{},M{},S{},B{},N{}",
+            radius,
+            if self.generations > 2 { self.generations } else { 0 },
+            u8::from(self.middle),
+            ranges_to_string(&self.survival),
+            ranges_to_string(&self.birth),
+            kind
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidFormatErr;
+
+impl fmt::Display for InvalidFormatErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Must match the pattern B[0-8]*/S[0-8]*(/C[0-9]+)?, [0-8]*/[0-8]*(/[0-9]+)? or R[0-9]+,C[0-9]+,M[01],S[0-9.,]*,B[0-9.,]*,N[MNH@]"
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidNeighborCountErr;
+
+impl fmt::Display for InvalidNeighborCountErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The number of neighbors must not exceed the size of the neighborhood")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidGenerationsErr;
+
+impl fmt::Display for InvalidGenerationsErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The number of states must be between 2 and 255")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidRadiusErr;
+
+impl fmt::Display for InvalidRadiusErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The radius must be between 1 and 500")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BirthOnZeroErr;
+
+impl fmt::Display for BirthOnZeroErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rules with birth on 0 neighbors must have 2 states")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RuleFromStrErr {
+    InvalidFormat(InvalidFormatErr),
+    InvalidNeighborCount(InvalidNeighborCountErr),
+    InvalidGenerations(InvalidGenerationsErr),
+    InvalidRadius(InvalidRadiusErr),
+    BirthOnZero(BirthOnZeroErr),
+}
+
+impl fmt::Display for RuleFromStrErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleFromStrErr::InvalidFormat(err) => err.fmt(f),
+            RuleFromStrErr::InvalidNeighborCount(err) => err.fmt(f),
+            RuleFromStrErr::InvalidGenerations(err) => err.fmt(f),
+            RuleFromStrErr::InvalidRadius(err) => err.fmt(f),
+            RuleFromStrErr::BirthOnZero(err) => err.fmt(f),
+        }
+    }
+}
+
+pub fn rule_count_matches(counts: &[u32], number_of_alive_neighbors: u32) -> bool {
+    counts.binary_search(&number_of_alive_neighbors).is_ok()
+}
+
+pub fn rule_is_life_like(rule: &Rule) -> bool {
+    rule.generations == 2
+        && !rule.middle
+        && rule.neighborhood == Neighborhood::Moore(1)
+        && rule.isotropic.is_none()
+        && rule.table.is_none()
+        && !rule_births_on_zero(rule)
+}
+
+pub fn rule_births_on_zero(rule: &Rule) -> bool {
+    rule.birth.first() == Some(&0)
+}
+
+pub fn rule_with_background(rule: &Rule, background: State) -> (Rule, State) {
+    let inverted = background == State::Alive;
+    let max_count = neighborhood_size(&rule.neighborhood) + u32::from(rule.middle);
+    let next_background = match rule.isotropic {
+        Some(_) => cell_iterate_configuration(rule, background, if inverted { u8::MAX } else { 0 }),
+        None => cell_iterate(rule, background, if inverted { max_count } else { 0 }),
+    };
+    let next_inverted = next_background == State::Alive;
+    let flip = |state: State, inverted: bool| if inverted { cell_toggle(&state) } else { state };
+    if rule.isotropic.is_some() {
+        let configurations = |state: State| -> Vec<NeighborConfiguration> {
+            (0..=u8::MAX)
+                .filter(|configuration| {
+                    let actual = if inverted { !configuration } else { *configuration };
+                    let next = cell_iterate_configuration(rule, flip(state, inverted), actual);
+                    flip(next, next_inverted) == State::Alive
+                })
+                .collect()
+        };
+        let isotropic = Isotropic {
+            birth: configurations(State::Dead),
+            survival: configurations(State::Alive),
+        };
+        let mut birth = configuration_counts(&isotropic.birth);
+        birth.sort_unstable();
+        birth.dedup();
+        let mut survival = configuration_counts(&isotropic.survival);
+        survival.sort_unstable();
+        survival.dedup();
+        let rule = Rule { birth, survival, isotropic: Some(isotropic), ..rule.clone() };
+        return (rule, next_background);
+    }
+    let counts = |state: State| -> Vec<u32> {
+        (0..=max_count)
+            .filter(|count| {
+                let actual = if inverted { max_count - count } else { *count };
+                flip(cell_iterate(rule, flip(state, inverted), actual), next_inverted)
+                    == State::Alive
+            })
+            .collect()
+    };
+    let rule = Rule { birth: counts(State::Dead), survival: counts(State::Alive), ..rule.clone() };
+    (rule, next_background)
+}
+
+fn digits_to_string(counts: &[u32]) -> String {
+    counts.iter().map(|count| count.to_string()).collect()
+}
+
+fn hensel_to_string(configurations: &[NeighborConfiguration]) -> String {
+    let mut as_str = String::new();
+    for count in 0..=8 {
+        let included: Vec<NeighborConfiguration> = configurations
+            .iter()
+            .copied()
+            .filter(|configuration| configuration.count_ones() == count)
+            .collect();
+        if included.is_empty() {
+            continue;
+        }
+        as_str.push_str(&count.to_string());
+        if included.len() == neighbor_configurations(count, None).len() {
+            continue;
+        }
+        let (letters_in, letters_out): (String, String) =
+            HENSEL_LETTERS[count as usize].chars().partition(|letter| {
+                neighbor_configurations(count, Some(*letter))
+                    .iter()
+                    .all(|configuration| included.contains(configuration))
+            });
+        if letters_in.len() <= letters_out.len() {
+            as_str.push_str(&letters_in);
+        } else {
+            as_str.push('-');
+            as_str.push_str(&letters_out);
+        }
+    }
+    as_str
+}
+
+fn ranges_to_string(counts: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for count in counts {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *count => *end = *count,
+            _ => ranges.push((*count, *count)),
+        }
+    }
+    ranges
+        .iter()
+        .map(
+            |(start, end)| {
+                if start == end { start.to_string() } else { format!("{start}..{end}") }
+            },
+        )
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn mask_cells(radius: u16) -> impl Iterator<Item = CartesianPoint> {
+    let radius = i32::from(radius);
+    (-radius..=radius).flat_map(move |y| (-radius..=radius).map(move |x| CartesianPoint::of(x, -y)))
+}
+
+fn mask_to_string(offsets: &[CartesianPoint], radius: u16) -> String {
+    let bits: Vec<bool> = mask_cells(radius).map(|cell| offsets.contains(&cell)).collect();
+    bits.chunks(4)
+        .map(|chunk| {
+            let digit = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, bit)| acc | (u32::from(*bit) << (3 - i)));
+            char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
+fn mask_try_from_str(as_str: &str, radius: u16) -> Result<Neighborhood, RuleFromStrErr> {
+    let side = 2 * usize::from(radius) + 1;
+    if as_str.chars().count() != (side * side).div_ceil(4) {
+        return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+    }
+    let digits = as_str
+        .chars()
+        .map(|c| c.to_digit(16).ok_or(RuleFromStrErr::InvalidFormat(InvalidFormatErr)))
+        .collect::<Result<Vec<u32>, RuleFromStrErr>>()?;
+    let bits = digits.iter().flat_map(|digit| (0..4).rev().map(move |i| (digit >> i) & 1 == 1));
+    Ok(Neighborhood::Custom(
+        mask_cells(radius).zip(bits).filter(|(_, bit)| *bit).map(|(cell, _)| cell).collect(),
+    ))
+}
+
+fn digits_try_from_str(as_str: &str) -> Result<Vec<u32>, RuleFromStrErr> {
+    as_str
+        .chars()
+        .map(|c| c.to_digit(10).ok_or(RuleFromStrErr::InvalidFormat(InvalidFormatErr)))
+        .collect()
+}
+
+fn hensel_try_from_str(as_str: &str) -> Result<Vec<NeighborConfiguration>, RuleFromStrErr> {
+    let mut configurations = Vec::new();
+    let mut chars = as_str.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c.to_digit(10).ok_or(RuleFromStrErr::InvalidFormat(InvalidFormatErr))?;
+        if count > 8 {
+            return Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr));
+        }
+        let negate = chars.next_if_eq(&'-').is_some();
+        let mut letters = Vec::new();
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            let letter = letter.to_ascii_lowercase();
+            if !HENSEL_LETTERS[count as usize].contains(letter) {
+                return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+            }
+            letters.push(letter);
+        }
+        if letters.is_empty() {
+            if negate {
+                return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+            }
+            configurations.extend(neighbor_configurations(count, None));
+            continue;
+        }
+        configurations.extend(neighbor_configurations(count, None).into_iter().filter(
+            |configuration| {
+                let selected = letters.iter().any(|letter| {
+                    neighbor_configurations(count, Some(*letter)).contains(configuration)
+                });
+                selected != negate
+            },
+        ));
+    }
+    configurations.sort_unstable();
+    configurations.dedup();
+    Ok(configurations)
+}
+
+fn configuration_counts(configurations: &[NeighborConfiguration]) -> Vec<u32> {
+    configurations.iter().map(|configuration| configuration.count_ones()).collect()
+}
+
+fn number_try_from_str(as_str: &str) -> Result<u32, RuleFromStrErr> {
+    if as_str.is_empty() || as_str.chars().any(|c| !c.is_ascii_digit()) {
+        return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+    }
+    as_str.parse::<u32>().map_err(|_| RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+}
+
+fn range_try_from_str(as_str: &str) -> Result<Vec<u32>, RuleFromStrErr> {
+    let (start, end) = match as_str.split_once("..").or_else(|| as_str.split_once('-')) {
+        Some((start, end)) => (number_try_from_str(start)?, number_try_from_str(end)?),
+        None => (number_try_from_str(as_str)?, number_try_from_str(as_str)?),
+    };
+    if end > neighborhood_size(&Neighborhood::Moore(500)) + 1 {
+        return Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr));
+    }
+    Ok((start..=end).collect())
+}
+
+fn generations_try_from_str(as_str: &str) -> Result<u8, RuleFromStrErr> {
+    match number_try_from_str(as_str)? {
+        generations @ 2..=255 => Ok(generations as u8),
+        _ => Err(RuleFromStrErr::InvalidGenerations(InvalidGenerationsErr)),
+    }
+}
+
+fn life_like_try_from_str(as_str: &str) -> Result<Rule, RuleFromStrErr> {
+    let (as_str, neighborhood) = match as_str.strip_suffix(['H', 'h']) {
+        Some(as_str) => (as_str, Neighborhood::Hexagonal(1)),
+        None => match as_str.strip_suffix(['V', 'v']) {
+            Some(as_str) => (as_str, Neighborhood::VonNeumann(1)),
+            None => (as_str, Neighborhood::Moore(1)),
+        },
+    };
+    let parts: Vec<&str> = as_str.split('/').collect();
+    if parts.len() != 2 && parts.len() != 3 {
+        return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+    }
+    let (birth, survival, prefixed) = match (parts[0].chars().next(), parts[1].chars().next()) {
+        (Some('B' | 'b'), Some('S' | 's')) => (&parts[0][1..], &parts[1][1..], true),
+        (Some('S' | 's'), Some('B' | 'b')) => (&parts[1][1..], &parts[0][1..], true),
+        _ => (parts[1], parts[0], false),
+    };
+    let generations = match parts.get(2) {
+        Some(part) if prefixed => match part.strip_prefix(['C', 'c']) {
+            Some(generations) => generations_try_from_str(generations)?,
+            None => generations_try_from_str(part)?,
+        },
+        Some(part) => generations_try_from_str(part)?,
+        None => 2,
+    };
+    if birth.chars().chain(survival.chars()).any(|c| c.is_ascii_alphabetic() || c == '-') {
+        if !prefixed || neighborhood != Neighborhood::Moore(1) {
+            return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+        }
+        let isotropic = Isotropic {
+            birth: hensel_try_from_str(birth)?,
+            survival: hensel_try_from_str(survival)?,
+        };
+        return Ok(Rule {
+            birth: configuration_counts(&isotropic.birth),
+            survival: configuration_counts(&isotropic.survival),
+            generations,
+            neighborhood,
+            middle: false,
+            isotropic: Some(isotropic),
+            table: None,
+        });
+    }
+    Ok(Rule {
+        birth: digits_try_from_str(birth)?,
+        survival: digits_try_from_str(survival)?,
+        generations,
+        neighborhood,
+        middle: false,
+        isotropic: None,
+        table: None,
+    })
+}
+
+fn larger_than_life_try_from_str(as_str: &str) -> Result<Rule, RuleFromStrErr> {
+    let mut radius: u32 = 1;
+    let mut rule = Rule { birth: Vec::new(), survival: Vec::new(), ..Default::default() };
+    let mut kind = "M";
+    let mut current_counts: Option<&mut Vec<u32>> = None;
+    for part in as_str.split(',') {
+        let part = part.trim();
+        let Some(key) = part.chars().next() else {
+            return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr));
+        };
+        if key.is_ascii_digit() {
+            let counts =
+                current_counts.as_mut().ok_or(RuleFromStrErr::InvalidFormat(InvalidFormatErr))?;
+            counts.extend(range_try_from_str(part)?);
+            continue;
+        }
+        let value = &part[key.len_utf8()..];
+        current_counts = None;
+        match key.to_ascii_uppercase() {
+            'R' => radius = number_try_from_str(value)?,
+            'C' => {
+                rule.generations = match number_try_from_str(value)? {
+                    0..=2 => 2,
+                    _ => generations_try_from_str(value)?,
+                }
+            }
+            'M' => {
+                rule.middle = match value {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)),
+                }
+            }
+            'S' | 'B' => {
+                let counts = if key.eq_ignore_ascii_case(&'S') {
+                    &mut rule.survival
+                } else {
+                    &mut rule.birth
+                };
+                if !value.is_empty() {
+                    counts.extend(range_try_from_str(value)?);
+                }
+                current_counts = Some(counts);
+            }
+            'N' => kind = value,
+            _ => return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)),
+        }
+    }
+    if !(1..=500).contains(&radius) {
+        return Err(RuleFromStrErr::InvalidRadius(InvalidRadiusErr));
+    }
+    let radius = radius as u16;
+    rule.neighborhood = match kind {
+        "M" | "m" => Neighborhood::Moore(radius),
+        "N" | "n" => Neighborhood::VonNeumann(radius),
+        "H" | "h" => Neighborhood::Hexagonal(radius),
+        _ => match kind.strip_prefix('@') {
+            Some(mask) => mask_try_from_str(mask, radius)?,
+            None => return Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)),
+        },
+    };
+    Ok(rule)
+}
+
+pub fn rule_try_from_str(as_str: &str) -> Result<Rule, RuleFromStrErr> {
+    let as_str = as_str.trim();
+    let mut rule = if as_str.starts_with(['R', 'r']) {
+        larger_than_life_try_from_str(as_str)?
+    } else {
+        life_like_try_from_str(as_str)?
+    };
+    rule.birth.sort_unstable();
+    rule.birth.dedup();
+    rule.survival.sort_unstable();
+    rule.survival.dedup();
+    let max_count = neighborhood_size(&rule.neighborhood) + u32::from(rule.middle);
+    if rule.birth.iter().chain(rule.survival.iter()).any(|count| *count > max_count) {
+        return Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr));
+    }
+    if rule_births_on_zero(&rule) && rule.generations > 2 {
+        return Err(RuleFromStrErr::BirthOnZero(BirthOnZeroErr));
+    }
+    Ok(rule)
+}
+
+pub fn rule_from_str(as_str: &str) -> Rule {
+    rule_try_from_str(as_str).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BirthOnZeroErr, InvalidFormatErr, InvalidGenerationsErr, InvalidNeighborCountErr,
+        InvalidRadiusErr, Rule, RuleFromStrErr, rule_births_on_zero, rule_count_matches,
+        rule_from_str, rule_is_life_like, rule_try_from_str, rule_with_background,
+    };
+    use crate::{
+        cell::State,
+        neighbor::{Neighborhood, neighborhood_from_str},
+        universe::CartesianPoint,
+    };
+
+    #[test]
+    fn invalid_format_err() {
+        assert_eq!(
+            InvalidFormatErr.to_string(),
+            "Must match the pattern B[0-8]*/S[0-8]*(/C[0-9]+)?, [0-8]*/[0-8]*(/[0-9]+)? or R[0-9]+,C[0-9]+,M[01],S[0-9.,]*,B[0-9.,]*,N[MNH@]"
+        );
+    }
+
+    #[test]
+    fn invalid_neighbor_count_err() {
+        assert_eq!(
+            InvalidNeighborCountErr.to_string(),
+            "The number of neighbors must not exceed the size of the neighborhood"
+        );
+    }
+
+    #[test]
+    fn invalid_generations_err() {
+        assert_eq!(
+            InvalidGenerationsErr.to_string(),
+            "The number of states must be between 2 and 255"
+        );
+    }
+
+    #[test]
+    fn invalid_radius_err() {
+        assert_eq!(InvalidRadiusErr.to_string(), "The radius must be between 1 and 500");
+    }
+
+    #[test]
+    fn birth_on_zero_err() {
+        assert_eq!(
+            BirthOnZeroErr.to_string(),
+            "Rules with birth on 0 neighbors must have 2 states"
+        );
+    }
+
+    #[test]
+    fn test_rule_is_life_like() {
+        assert!(rule_is_life_like(&Rule::default()));
+        assert!(rule_is_life_like(&rule_from_str("B36/S23")));
+        assert!(!rule_is_life_like(&rule_from_str("B2/S/C3")));
+        assert!(!rule_is_life_like(&rule_from_str("B2/SV")));
+        assert!(!rule_is_life_like(&rule_from_str("B2/SH")));
+        assert!(!rule_is_life_like(&rule_from_str("R1,C0,M1,S2..3,B3..3,NM")));
+        assert!(!rule_is_life_like(&rule_from_str("R2,C0,M0,S2..3,B3..3,NM")));
+        assert!(!rule_is_life_like(&rule_from_str("B2-a/S12")));
+    }
+
+    #[test]
+    fn test_rule_with_background() {
+        let rule = rule_from_str("B0123478/S01234678");
+        assert!(rule_births_on_zero(&rule));
+        assert!(!rule_is_life_like(&rule));
+        let (dead, background) = rule_with_background(&rule, State::Dead);
+        assert_eq!((dead.birth, dead.survival, background), (vec![5, 6], vec![5], State::Alive));
+        let (alive, background) = rule_with_background(&rule, State::Alive);
+        assert_eq!((alive.birth, alive.survival, background), (vec![3], vec![2, 3], State::Alive));
+        let (alive, background) = rule_with_background(&rule_from_str("B0/S"), State::Alive);
+        assert_eq!((alive.birth, alive.survival, background), (vec![], vec![8], State::Dead));
+        let (unchanged, background) = rule_with_background(&Rule::default(), State::Dead);
+        assert_eq!((unchanged, background), (Rule::default(), State::Dead));
+        let (isotropic, background) = rule_with_background(&rule_from_str("B0/S2-a"), State::Alive);
+        let isotropic = isotropic.isotropic.unwrap();
+        assert_eq!(background, State::Dead);
+        assert_eq!(isotropic.birth.len(), 20);
+        assert!(!isotropic.birth.contains(&!0b00000011));
+        assert_eq!(isotropic.survival, vec![0b11111111]);
+    }
+
+    #[test]
+    fn test_rule_try_from_str_isotropic() {
+        let rule = rule_from_str("B2-a/S12");
+        assert_eq!(rule.birth, vec![2]);
+        assert_eq!(rule.survival, vec![1, 2]);
+        let isotropic = rule.isotropic.unwrap();
+        assert_eq!(isotropic.birth.len(), 20);
+        assert!(!isotropic.birth.contains(&0b00000011));
+        assert!(isotropic.birth.contains(&0b00000101));
+        assert_eq!(isotropic.survival.len(), 36);
+        let rule = rule_from_str("B3ai/S2-c/C4");
+        assert_eq!(rule.generations, 4);
+        assert_eq!(rule.isotropic.unwrap().birth.len(), 8);
+        assert_eq!(
+            rule_try_from_str("B2z/S"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B2-/S"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B2a/S1V"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(rule_try_from_str("1/2a"), Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)));
+        assert_eq!(
+            rule_try_from_str("B0c/S"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+    }
+
+    #[test]
+    fn test_rule_default() {
+        assert_eq!(Rule::default(), rule_from_str("B3/S23"));
+        assert_eq!(Rule::default().to_string(), "B3/S23");
+    }
+
+    #[test]
+    fn test_rule_count_matches() {
+        assert!(rule_count_matches(&[2, 3], 2));
+        assert!(rule_count_matches(&[2, 3], 3));
+        assert!(!rule_count_matches(&[2, 3], 4));
+        assert!(!rule_count_matches(&[], 0));
+    }
+
+    #[test]
+    fn test_rule_try_from_str() {
+        let high_life = Rule { birth: vec![3, 6], survival: vec![2, 3], ..Default::default() };
+        assert_eq!(rule_try_from_str("B36/S23"), Ok(high_life.clone()));
+        assert_eq!(rule_try_from_str("b36/s23"), Ok(high_life.clone()));
+        assert_eq!(rule_try_from_str("S23/B36"), Ok(high_life.clone()));
+        assert_eq!(rule_try_from_str("23/36"), Ok(high_life.clone()));
+        assert_eq!(rule_try_from_str(" B63/S32 "), Ok(high_life));
+        assert_eq!(
+            rule_try_from_str("B2/S"),
+            Ok(Rule { birth: vec![2], survival: vec![], ..Default::default() })
+        );
+    }
+
+    #[test]
+    fn test_rule_try_from_str_generations() {
+        let brians_brain =
+            Rule { birth: vec![2], survival: vec![], generations: 3, ..Default::default() };
+        assert_eq!(rule_try_from_str("B2/S/C3"), Ok(brians_brain.clone()));
+        assert_eq!(rule_try_from_str("b2/s/c3"), Ok(brians_brain.clone()));
+        assert_eq!(rule_try_from_str("B2/S/3"), Ok(brians_brain.clone()));
+        assert_eq!(rule_try_from_str("/2/3"), Ok(brians_brain));
+        assert_eq!(
+            rule_try_from_str("345/2/4"),
+            Ok(Rule {
+                birth: vec![2],
+                survival: vec![3, 4, 5],
+                generations: 4,
+                ..Default::default()
+            })
+        );
+        assert_eq!(rule_try_from_str("B3/S23/C2"), Ok(Rule::default()));
+    }
+
+    #[test]
+    fn test_rule_try_from_str_neighborhood() {
+        assert_eq!(
+            rule_try_from_str("B2/S34H"),
+            Ok(Rule {
+                birth: vec![2],
+                survival: vec![3, 4],
+                neighborhood: Neighborhood::Hexagonal(1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            rule_try_from_str("B2/S013V"),
+            Ok(Rule {
+                birth: vec![2],
+                survival: vec![0, 1, 3],
+                neighborhood: Neighborhood::VonNeumann(1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            rule_try_from_str("B2/S/C3h"),
+            Ok(Rule {
+                birth: vec![2],
+                survival: vec![],
+                generations: 3,
+                neighborhood: Neighborhood::Hexagonal(1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            rule_try_from_str("R2,C0,M0,S2..4,B3,NH"),
+            Ok(Rule {
+                birth: vec![3],
+                survival: vec![2, 3, 4],
+                neighborhood: Neighborhood::Hexagonal(2),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M0,S1,B1,N@A28"),
+            Ok(Rule {
+                birth: vec![1],
+                survival: vec![1],
+                neighborhood: Neighborhood::Custom(vec![
+                    CartesianPoint::of(-1, 1),
+                    CartesianPoint::of(1, 1),
+                    CartesianPoint::of(-1, -1),
+                    CartesianPoint::of(1, -1),
+                ]),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_rule_try_from_str_neighborhood_err() {
+        assert_eq!(
+            rule_try_from_str("B2/S7H"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B2/S013V5"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M0,S1,B5,N@A28"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M0,S1,B1,N@A2"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M0,S1,B1,N@A2G"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M0,S1,B1,NX"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+    }
+
+    #[test]
+    fn test_rule_try_from_str_larger_than_life() {
+        assert_eq!(
+            rule_try_from_str("R5,C0,M1,S34..58,B34..45,NM"),
+            Ok(Rule {
+                birth: (34..=45).collect(),
+                survival: (34..=58).collect(),
+                generations: 2,
+                neighborhood: Neighborhood::Moore(5),
+                middle: true,
+                isotropic: None,
+                table: None,
+            })
+        );
+        assert_eq!(
+            rule_try_from_str("r2,c3,m0,s2-3,5,b3,nn"),
+            Ok(Rule {
+                birth: vec![3],
+                survival: vec![2, 3, 5],
+                generations: 3,
+                neighborhood: Neighborhood::VonNeumann(2),
+                middle: false,
+                isotropic: None,
+                table: None,
+            })
+        );
+        assert_eq!(rule_try_from_str("R1,C0,M0,S2..3,B3..3,NM"), Ok(Rule::default()));
+        assert_eq!(
+            rule_try_from_str("R3,S,B10"),
+            Ok(Rule {
+                birth: vec![10],
+                survival: vec![],
+                neighborhood: Neighborhood::Moore(3),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_rule_try_from_str_err() {
+        assert_eq!(rule_try_from_str(""), Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)));
+        assert_eq!(rule_try_from_str("B3"), Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)));
+        assert_eq!(
+            rule_try_from_str("B3/S23/C3/D"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/S23/C"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/S23/Cx"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/S23/C1"),
+            Err(RuleFromStrErr::InvalidGenerations(InvalidGenerationsErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/S23/C256"),
+            Err(RuleFromStrErr::InvalidGenerations(InvalidGenerationsErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/B23"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B3/S2x"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B39/S23"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("B0123478/S01234678/C3"),
+            Err(RuleFromStrErr::BirthOnZero(BirthOnZeroErr))
+        );
+    }
+
+    #[test]
+    fn test_rule_try_from_str_larger_than_life_err() {
+        assert_eq!(
+            rule_try_from_str("R5,C0,M2,S34..58,B34..45,NM"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,C0,M1,S34..58,B34..45,NX"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,3,S34..58"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,C0,,S34..58"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,C0,M1,S34..x,B34..45,NM"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,C0,M1,S34..58,B34..4294967295,NM"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R5,C0,M1,S34..58,Bé,NM"),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(rule_try_from_str("R5,é"), Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr)));
+        assert_eq!(
+            rule_try_from_str("R0,C0,M1,S1,B1,NM"),
+            Err(RuleFromStrErr::InvalidRadius(InvalidRadiusErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R501,C0,M1,S1,B1,NM"),
+            Err(RuleFromStrErr::InvalidRadius(InvalidRadiusErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R1,C0,M1,S1,B10,NM"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R2,C0,M0,S1,B13,NN"),
+            Err(RuleFromStrErr::InvalidNeighborCount(InvalidNeighborCountErr))
+        );
+        assert_eq!(
+            rule_try_from_str("R2,C3,M0,S1,B0..3,NM"),
+            Err(RuleFromStrErr::BirthOnZero(BirthOnZeroErr))
+        );
+    }
+
+    #[test]
+    fn test_rule_to_string() {
+        assert_eq!(rule_from_str("23/36").to_string(), "B36/S23");
+        assert_eq!(rule_from_str("B2/S").to_string(), "B2/S");
+        assert_eq!(rule_from_str("B3678/S34678").to_string(), "B3678/S34678");
+        assert_eq!(rule_from_str("/2/3").to_string(), "B2/S/C3");
+        assert_eq!(rule_from_str("B3/S23/C2").to_string(), "B3/S23");
+        assert_eq!(
+            rule_from_str("R5,C0,M1,S34..58,B34..45,NM").to_string(),
+            "R5,C0,M1,S34..58,B34..45,NM"
+        );
+        assert_eq!(rule_from_str("R2,C3,M0,S2-3,5,B3,NN").to_string(), "R2,C3,M0,S2..3,5,B3,NN");
+        assert_eq!(rule_from_str("R1,C0,M1,S,B3,NM").to_string(), "R1,C0,M1,S,B3,NM");
+        assert_eq!(rule_from_str("R1,C0,M0,S2..3,B3,NM").to_string(), "B3/S23");
+        assert_eq!(rule_from_str("B2/S34H").to_string(), "B2/S34H");
+        assert_eq!(rule_from_str("B2/S013V").to_string(), "B2/S013V");
+        assert_eq!(rule_from_str("/2/3H").to_string(), "B2/S/C3H");
+        assert_eq!(rule_from_str("R2,C0,M1,S2..4,B3,NH").to_string(), "R2,C0,M1,S2..4,B3,NH");
+        assert_eq!(rule_from_str("R1,C0,M0,S1,B1,N@a28").to_string(), "R1,C0,M0,S1,B1,N@A28");
+        assert_eq!(rule_from_str("B2-a/S12").to_string(), "B2-a/S12");
+        assert_eq!(rule_from_str("B2ceikn/S1ce2").to_string(), "B2-a/S12");
+        assert_eq!(rule_from_str("B3ai4/S2-c/C4").to_string(), "B3ai4/S2-c/C4");
+        assert_eq!(
+            Rule {
+                birth: vec![1],
+                survival: vec![],
+                neighborhood: neighborhood_from_str([
+                    "⬛⬛⬜⬛⬛",
+                    "⬛⬛⬛⬛⬛",
+                    "⬜⬛⬛⬛⬜",
+                    "⬛⬛⬛⬛⬛",
+                    "⬛⬛⬜⬛⬛",
+                ]),
+                ..Default::default()
+            }
+            .to_string(),
+            "R2,C0,M0,S,B1,N@2022020"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    cell::State,
+    random::{random_from_seed, random_next_f64},
+    topology::Bounds,
+    transform::{TRANSFORMS, Transform, transform_point},
+    universe::{CartesianPoint, Universe},
+};
+use std::{collections::HashMap, fmt};
+
+pub const SOUP_PRESET_ID: &str = "random";
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Symmetry {
+    #[default]
+    C1,
+    C2,
+    C4,
+    D4,
+    D8,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Soup {
+    pub bounds: Bounds,
+    pub density: f64,
+    pub seed: u64,
+    pub symmetry: Symmetry,
+}
+
+impl Default for Soup {
+    fn default() -> Self {
+        Soup { bounds: Bounds::of(-8, -8, 7, 7), density: 0.5, seed: 0, symmetry: Symmetry::C1 }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NonSquareBoundsErr;
+
+impl fmt::Display for NonSquareBoundsErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The bounds must be square for the C4 and D8 symmetries")
+    }
+}
+
+fn symmetry_transforms(symmetry: Symmetry) -> &'static [Transform] {
+    match symmetry {
+        Symmetry::C1 => &[Transform::Identity],
+        Symmetry::C2 => &[Transform::Identity, Transform::Rotate180],
+        Symmetry::C4 => {
+            &[Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270]
+        }
+        Symmetry::D4 => {
+            &[Transform::Identity, Transform::FlipX, Transform::FlipY, Transform::Rotate180]
+        }
+        Symmetry::D8 => &TRANSFORMS,
+    }
+}
+
+pub fn soup_try_bounds(soup: &Soup) -> Result<&Bounds, NonSquareBoundsErr> {
+    let bounds = &soup.bounds;
+    let square = i64::from(bounds.max.x) - i64::from(bounds.min.x)
+        == i64::from(bounds.max.y) - i64::from(bounds.min.y);
+    match soup.symmetry {
+        Symmetry::C4 | Symmetry::D8 if !square => Err(NonSquareBoundsErr),
+        _ => Ok(bounds),
+    }
+}
+
+fn orbit(point: &CartesianPoint, bounds: &Bounds, symmetry: Symmetry) -> Vec<CartesianPoint> {
+    let center_x = bounds.min.x + bounds.max.x;
+    let center_y = bounds.min.y + bounds.max.y;
+    let doubled = CartesianPoint::of(2 * point.x - center_x, 2 * point.y - center_y);
+    symmetry_transforms(symmetry)
+        .iter()
+        .map(|transform| {
+            let image = transform_point(*transform, &doubled);
+            CartesianPoint::of((image.x + center_x) / 2, (image.y + center_y) / 2)
+        })
+        .collect()
+}
+
+pub fn soup_generate(soup: &Soup) -> Result<Universe, NonSquareBoundsErr> {
+    let bounds = soup_try_bounds(soup)?;
+    let mut random = random_from_seed(soup.seed);
+    let mut value = HashMap::new();
+    for y in (bounds.min.y..=bounds.max.y).rev() {
+        for x in bounds.min.x..=bounds.max.x {
+            let point = CartesianPoint::of(x, y);
+            let orbit = orbit(&point, bounds, soup.symmetry);
+            let representative = orbit.iter().max_by_key(|p| (p.y, -p.x)).unwrap();
+            if representative != &point {
+                continue;
+            }
+            if random_next_f64(&mut random) < soup.density {
+                for image in orbit {
+                    value.insert(image, State::Alive);
+                }
+            }
+        }
+    }
+    Ok(Universe::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NonSquareBoundsErr, Soup, Symmetry, soup_generate, soup_try_bounds};
+    use crate::{
+        topology::Bounds,
+        transform::{TRANSFORMS, Transform},
+        universe::{CartesianPoint, universe_transform, universe_translate},
+    };
+
+    #[test]
+    fn test_soup_generate() {
+        let soup = Soup { seed: 42, ..Default::default() };
+        let universe = soup_generate(&soup).unwrap();
+        assert_eq!(soup_generate(&soup), Ok(universe.clone()));
+        assert_ne!(soup_generate(&Soup { seed: 43, ..Default::default() }), Ok(universe.clone()));
+        assert!((96..160).contains(&universe.value.len()));
+        assert!(universe.value.keys().all(|p| (-8..=7).contains(&p.x) && (-8..=7).contains(&p.y)));
+        assert_eq!(
+            soup_generate(&Soup { density: 0.0, ..soup.clone() }).map(|u| u.value.len()),
+            Ok(0)
+        );
+        assert_eq!(soup_generate(&Soup { density: 1.0, ..soup }).map(|u| u.value.len()), Ok(256));
+    }
+
+    #[test]
+    fn test_soup_symmetry() {
+        let centered = |symmetry| {
+            let soup = Soup { bounds: Bounds::of(-5, -5, 5, 5), seed: 7, symmetry, density: 0.5 };
+            soup_generate(&soup).unwrap()
+        };
+        let universe = centered(Symmetry::C1);
+        assert_ne!(universe_transform(&universe, Transform::Rotate180), Ok(universe.clone()));
+        let universe = centered(Symmetry::C2);
+        assert_eq!(universe_transform(&universe, Transform::Rotate180), Ok(universe.clone()));
+        assert_ne!(universe_transform(&universe, Transform::Rotate90), Ok(universe.clone()));
+        let universe = centered(Symmetry::C4);
+        assert_eq!(universe_transform(&universe, Transform::Rotate90), Ok(universe.clone()));
+        assert_ne!(universe_transform(&universe, Transform::FlipX), Ok(universe.clone()));
+        let universe = centered(Symmetry::D4);
+        assert_eq!(universe_transform(&universe, Transform::FlipX), Ok(universe.clone()));
+        assert_eq!(universe_transform(&universe, Transform::FlipY), Ok(universe.clone()));
+        let universe = centered(Symmetry::D8);
+        for transform in TRANSFORMS {
+            assert_eq!(universe_transform(&universe, transform), Ok(universe.clone()));
+        }
+    }
+
+    #[test]
+    fn test_soup_symmetry_even_bounds() {
+        let soup =
+            Soup { bounds: Bounds::of(0, 0, 15, 15), symmetry: Symmetry::D8, ..Default::default() };
+        let universe = soup_generate(&soup).unwrap();
+        let flipped = universe_transform(&universe, Transform::FlipX).unwrap();
+        assert_eq!(universe_translate(&flipped, &CartesianPoint::of(15, 0)), Ok(universe.clone()));
+        assert_eq!(universe_transform(&universe, Transform::Transpose), Ok(universe));
+    }
+
+    #[test]
+    fn test_soup_try_bounds() {
+        let soup = Soup { bounds: Bounds::of(0, 0, 15, 7), ..Default::default() };
+        assert_eq!(soup_try_bounds(&soup), Ok(&Bounds::of(0, 0, 15, 7)));
+        assert_eq!(
+            soup_try_bounds(&Soup { symmetry: Symmetry::D4, ..soup.clone() }),
+            Ok(&soup.bounds)
+        );
+        assert_eq!(
+            soup_try_bounds(&Soup { symmetry: Symmetry::C4, ..soup.clone() }),
+            Err(NonSquareBoundsErr)
+        );
+        assert_eq!(
+            soup_generate(&Soup { symmetry: Symmetry::D8, ..soup }),
+            Err(NonSquareBoundsErr)
+        );
+        let full = Soup {
+            bounds: Bounds::of(i32::MIN, i32::MIN, i32::MAX, i32::MAX),
+            symmetry: Symmetry::D8,
+            ..Default::default()
+        };
+        assert_eq!(soup_try_bounds(&full), Ok(&full.bounds));
+    }
+
+    #[test]
+    fn non_square_bounds_err() {
+        assert_eq!(
+            NonSquareBoundsErr.to_string(),
+            "The bounds must be square for the C4 and D8 symmetries"
+        );
+    }
+}
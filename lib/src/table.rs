@@ -0,0 +1,726 @@
+use crate::{
+    neighbor::Neighborhood,
+    rule::{InvalidGenerationsErr, Rule},
+    universe::CartesianPoint,
+};
+use std::{collections::HashMap, fmt};
+
+type StateSet = [u64; 4];
+
+const MOORE_OFFSETS: [(i32, i32); 8] =
+    [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+
+const VON_NEUMANN_OFFSETS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+const MOORE_TREE_ORDER: [usize; 8] = [7, 1, 5, 3, 0, 6, 2, 4];
+
+const VON_NEUMANN_TREE_ORDER: [usize; 4] = [0, 3, 1, 2];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TableSymmetry {
+    None,
+    Rotate4,
+    Rotate8,
+    ReflectHorizontal,
+    Rotate4Reflect,
+    Rotate8Reflect,
+    Permute,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableSlot {
+    pub allowed: StateSet,
+    pub bound_to: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableOutput {
+    State(u8),
+    Slot(usize),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableTransition {
+    pub slots: Vec<TableSlot>,
+    pub output: TableOutput,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableLookup {
+    Transitions {
+        symmetry: TableSymmetry,
+        permutations: Vec<Vec<usize>>,
+        transitions: Vec<TableTransition>,
+    },
+    Tree(Vec<Vec<u32>>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Table {
+    pub name: String,
+    pub states: u8,
+    pub neighborhood: Neighborhood,
+    pub lookup: TableLookup,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MissingTableErr;
+
+impl fmt::Display for MissingTableErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The rule must contain a @TABLE or @TREE section")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidHeaderErr;
+
+impl fmt::Display for InvalidHeaderErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The table must declare n_states and neighborhood")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedNeighborhoodErr;
+
+impl fmt::Display for UnsupportedNeighborhoodErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The neighborhood must be vonNeumann or Moore")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedSymmetryErr;
+
+impl fmt::Display for UnsupportedSymmetryErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The symmetries are not supported for the neighborhood")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidVariableErr;
+
+impl fmt::Display for InvalidVariableErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Must match the pattern var [a-zA-Z0-9_]+={{[0-9a-zA-Z_,]+}}")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidTransitionErr;
+
+impl fmt::Display for InvalidTransitionErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Every transition must list the cell, its neighbors and the new state, using states or variables declared before"
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidTreeErr;
+
+impl fmt::Display for InvalidTreeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The tree must declare num_states, num_neighbors and num_nodes followed by its nodes, each referencing earlier nodes one level below"
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TableFromStrErr {
+    MissingTable(MissingTableErr),
+    InvalidHeader(InvalidHeaderErr),
+    InvalidGenerations(InvalidGenerationsErr),
+    UnsupportedNeighborhood(UnsupportedNeighborhoodErr),
+    UnsupportedSymmetry(UnsupportedSymmetryErr),
+    InvalidVariable(InvalidVariableErr),
+    InvalidTransition(InvalidTransitionErr),
+    InvalidTree(InvalidTreeErr),
+}
+
+impl fmt::Display for TableFromStrErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableFromStrErr::MissingTable(err) => err.fmt(f),
+            TableFromStrErr::InvalidHeader(err) => err.fmt(f),
+            TableFromStrErr::InvalidGenerations(err) => err.fmt(f),
+            TableFromStrErr::UnsupportedNeighborhood(err) => err.fmt(f),
+            TableFromStrErr::UnsupportedSymmetry(err) => err.fmt(f),
+            TableFromStrErr::InvalidVariable(err) => err.fmt(f),
+            TableFromStrErr::InvalidTransition(err) => err.fmt(f),
+            TableFromStrErr::InvalidTree(err) => err.fmt(f),
+        }
+    }
+}
+
+fn state_set_of(state: u8) -> StateSet {
+    let mut set = [0; 4];
+    set[usize::from(state / 64)] |= 1 << (state % 64);
+    set
+}
+
+fn state_set_contains(set: &StateSet, state: u8) -> bool {
+    set[usize::from(state / 64)] & (1 << (state % 64)) != 0
+}
+
+fn state_set_union(a: &StateSet, b: &StateSet) -> StateSet {
+    [a[0] | b[0], a[1] | b[1], a[2] | b[2], a[3] | b[3]]
+}
+
+fn states_try_from_str(as_str: &str) -> Result<u8, TableFromStrErr> {
+    match as_str.trim().parse::<u16>() {
+        Ok(states) if (2..=255).contains(&states) => Ok(states as u8),
+        _ => Err(TableFromStrErr::InvalidGenerations(InvalidGenerationsErr)),
+    }
+}
+
+fn neighborhood_try_from_str(as_str: &str) -> Result<Neighborhood, TableFromStrErr> {
+    match as_str.trim() {
+        "vonNeumann" => Ok(Neighborhood::VonNeumann(1)),
+        "Moore" => Ok(Neighborhood::Moore(1)),
+        _ => Err(TableFromStrErr::UnsupportedNeighborhood(UnsupportedNeighborhoodErr)),
+    }
+}
+
+fn symmetry_try_from_str(
+    as_str: &str,
+    neighborhood: &Neighborhood,
+) -> Result<TableSymmetry, TableFromStrErr> {
+    let symmetry = match as_str.trim() {
+        "none" => TableSymmetry::None,
+        "rotate4" => TableSymmetry::Rotate4,
+        "rotate8" => TableSymmetry::Rotate8,
+        "reflect_horizontal" => TableSymmetry::ReflectHorizontal,
+        "rotate4reflect" => TableSymmetry::Rotate4Reflect,
+        "rotate8reflect" => TableSymmetry::Rotate8Reflect,
+        "permute" => TableSymmetry::Permute,
+        _ => return Err(TableFromStrErr::UnsupportedSymmetry(UnsupportedSymmetryErr)),
+    };
+    if neighborhood == &Neighborhood::VonNeumann(1)
+        && matches!(symmetry, TableSymmetry::Rotate8 | TableSymmetry::Rotate8Reflect)
+    {
+        return Err(TableFromStrErr::UnsupportedSymmetry(UnsupportedSymmetryErr));
+    }
+    Ok(symmetry)
+}
+
+fn symmetry_permutations(symmetry: TableSymmetry, len: usize) -> Vec<Vec<usize>> {
+    let (step, reflect) = match symmetry {
+        TableSymmetry::None | TableSymmetry::Permute => (len, false),
+        TableSymmetry::Rotate4 => (len / 4, false),
+        TableSymmetry::Rotate8 => (1, false),
+        TableSymmetry::ReflectHorizontal => (len, true),
+        TableSymmetry::Rotate4Reflect => (len / 4, true),
+        TableSymmetry::Rotate8Reflect => (1, true),
+    };
+    let mut permutations = Vec::new();
+    for rotation in (0..len).step_by(step) {
+        permutations.push((0..len).map(|i| (i + rotation) % len).collect());
+        if reflect {
+            permutations.push((0..len).map(|i| (len - i + rotation) % len).collect());
+        }
+    }
+    permutations
+}
+
+fn variable_try_from_str(
+    as_str: &str,
+    states: u8,
+    variables: &HashMap<String, StateSet>,
+) -> Result<(String, StateSet), TableFromStrErr> {
+    let (name, values) =
+        as_str.split_once('=').ok_or(TableFromStrErr::InvalidVariable(InvalidVariableErr))?;
+    let values = values
+        .trim()
+        .strip_prefix('{')
+        .and_then(|values| values.strip_suffix('}'))
+        .ok_or(TableFromStrErr::InvalidVariable(InvalidVariableErr))?;
+    let mut set = [0; 4];
+    for value in values.split(',').map(str::trim) {
+        set = match (value.parse::<u8>(), variables.get(value)) {
+            (Ok(state), _) if state < states => state_set_union(&set, &state_set_of(state)),
+            (Err(_), Some(other)) => state_set_union(&set, other),
+            _ => return Err(TableFromStrErr::InvalidVariable(InvalidVariableErr)),
+        };
+    }
+    let name = name.trim();
+    if name.is_empty() || name.contains(|c: char| !c.is_alphanumeric() && c != '_') {
+        return Err(TableFromStrErr::InvalidVariable(InvalidVariableErr));
+    }
+    Ok((name.into(), set))
+}
+
+fn transition_try_from_str(
+    as_str: &str,
+    states: u8,
+    len: usize,
+    variables: &HashMap<String, StateSet>,
+) -> Result<TableTransition, TableFromStrErr> {
+    let tokens: Vec<String> = if as_str.contains(',') {
+        as_str.split(',').map(|token| token.trim().into()).collect()
+    } else if as_str.chars().all(|c| c.is_ascii_digit()) {
+        as_str.chars().map(String::from).collect()
+    } else {
+        return Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr));
+    };
+    if tokens.len() != len + 2 {
+        return Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr));
+    }
+    let mut slots = Vec::new();
+    let mut first_slot: HashMap<&str, usize> = HashMap::new();
+    for (i, token) in tokens[..len + 1].iter().enumerate() {
+        let slot = match (token.parse::<u8>(), variables.get(token)) {
+            (Ok(state), _) if state < states => {
+                TableSlot { allowed: state_set_of(state), bound_to: None }
+            }
+            (Err(_), Some(set)) => {
+                TableSlot { allowed: *set, bound_to: first_slot.get(token.as_str()).copied() }
+            }
+            _ => return Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr)),
+        };
+        first_slot.entry(token).or_insert(i);
+        slots.push(slot);
+    }
+    let token = tokens[len + 1].as_str();
+    let output = match (token.parse::<u8>(), first_slot.get(token)) {
+        (Ok(state), _) if state < states => TableOutput::State(state),
+        (Err(_), Some(slot)) => TableOutput::Slot(*slot),
+        _ => return Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr)),
+    };
+    Ok(TableTransition { slots, output })
+}
+
+fn table_section_try_from_lines(name: String, lines: &[&str]) -> Result<Table, TableFromStrErr> {
+    let mut states = None;
+    let mut neighborhood = None;
+    let mut symmetry = "none";
+    for line in lines {
+        if let Some((key, value)) = line.split_once(':') {
+            match key.trim() {
+                "n_states" => states = Some(states_try_from_str(value)?),
+                "neighborhood" => neighborhood = Some(neighborhood_try_from_str(value)?),
+                "symmetries" => symmetry = value,
+                _ => return Err(TableFromStrErr::InvalidHeader(InvalidHeaderErr)),
+            }
+        }
+    }
+    let (Some(states), Some(neighborhood)) = (states, neighborhood) else {
+        return Err(TableFromStrErr::InvalidHeader(InvalidHeaderErr));
+    };
+    let symmetry = symmetry_try_from_str(symmetry, &neighborhood)?;
+    let len = if neighborhood == Neighborhood::Moore(1) { 8 } else { 4 };
+    let mut variables = HashMap::new();
+    let mut transitions = Vec::new();
+    for line in lines.iter().filter(|line| !line.contains(':')) {
+        if let Some(variable) = line.strip_prefix("var ") {
+            let (name, set) = variable_try_from_str(variable, states, &variables)?;
+            variables.insert(name, set);
+        } else {
+            transitions.push(transition_try_from_str(line, states, len, &variables)?);
+        }
+    }
+    let permutations = symmetry_permutations(symmetry, len);
+    Ok(Table {
+        name,
+        states,
+        neighborhood,
+        lookup: TableLookup::Transitions { symmetry, permutations, transitions },
+    })
+}
+
+fn tree_section_try_from_lines(name: String, lines: &[&str]) -> Result<Table, TableFromStrErr> {
+    let mut header: HashMap<&str, u32> = HashMap::new();
+    let mut levels: Vec<u32> = Vec::new();
+    let mut nodes: Vec<Vec<u32>> = Vec::new();
+    for line in lines {
+        if let Some((key, value)) = line.split_once('=') {
+            let value =
+                value.trim().parse().map_err(|_| TableFromStrErr::InvalidTree(InvalidTreeErr))?;
+            header.insert(key.trim(), value);
+            continue;
+        }
+        let numbers: Vec<u32> = line
+            .split_whitespace()
+            .map(|number| number.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| TableFromStrErr::InvalidTree(InvalidTreeErr))?;
+        let Some((level, children)) = numbers.split_first() else {
+            return Err(TableFromStrErr::InvalidTree(InvalidTreeErr));
+        };
+        levels.push(*level);
+        nodes.push(children.to_vec());
+    }
+    let (Some(states), Some(neighbors), Some(len)) =
+        (header.get("num_states"), header.get("num_neighbors"), header.get("num_nodes"))
+    else {
+        return Err(TableFromStrErr::InvalidTree(InvalidTreeErr));
+    };
+    let states = states_try_from_str(&states.to_string())?;
+    let neighborhood = match neighbors {
+        4 => Neighborhood::VonNeumann(1),
+        8 => Neighborhood::Moore(1),
+        _ => return Err(TableFromStrErr::UnsupportedNeighborhood(UnsupportedNeighborhoodErr)),
+    };
+    let valid = nodes.len() == *len as usize
+        && levels.last() == Some(&(neighbors + 1))
+        && nodes.iter().zip(levels.iter()).enumerate().all(|(i, (children, level))| {
+            children.len() == usize::from(states)
+                && children.iter().all(|child| match level {
+                    1 => *child < u32::from(states),
+                    _ => (*child as usize) < i && levels[*child as usize] + 1 == *level,
+                })
+        });
+    if !valid {
+        return Err(TableFromStrErr::InvalidTree(InvalidTreeErr));
+    }
+    Ok(Table { name, states, neighborhood, lookup: TableLookup::Tree(nodes) })
+}
+
+pub fn table_try_from_str(as_str: &str) -> Result<Table, TableFromStrErr> {
+    let mut name = String::new();
+    let mut section = "";
+    let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+    for line in as_str.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('@') {
+            let mut parts = header.split_whitespace();
+            section = parts.next().unwrap_or_default();
+            if section == "RULE" {
+                name = parts.next().unwrap_or_default().into();
+            }
+            sections.entry(section).or_default();
+            continue;
+        }
+        if let Some(lines) = sections.get_mut(section) {
+            lines.push(line);
+        }
+    }
+    if let Some(lines) = sections.get("TABLE") {
+        return table_section_try_from_lines(name, lines);
+    }
+    if let Some(lines) = sections.get("TREE") {
+        return tree_section_try_from_lines(name, lines);
+    }
+    Err(TableFromStrErr::MissingTable(MissingTableErr))
+}
+
+pub fn table_from_str(as_str: &str) -> Table {
+    table_try_from_str(as_str).unwrap()
+}
+
+pub fn table_offsets(table: &Table) -> impl Iterator<Item = CartesianPoint> {
+    let offsets: &[(i32, i32)] = match table.neighborhood {
+        Neighborhood::VonNeumann(_) => &VON_NEUMANN_OFFSETS,
+        _ => &MOORE_OFFSETS,
+    };
+    offsets.iter().map(|(x, y)| CartesianPoint::of(*x, *y))
+}
+
+fn slot_matches(slot: &TableSlot, values: &[u8], i: usize) -> bool {
+    state_set_contains(&slot.allowed, values[i])
+        && slot.bound_to.is_none_or(|bound_to| values[bound_to] == values[i])
+}
+
+fn permute_matches(
+    transition: &TableTransition,
+    neighbors: &[u8],
+    values: &mut [u8; 9],
+    used: &mut [bool; 8],
+    slot: usize,
+) -> bool {
+    if slot > neighbors.len() {
+        return true;
+    }
+    for i in 0..neighbors.len() {
+        if used[i] || (0..i).any(|j| !used[j] && neighbors[j] == neighbors[i]) {
+            continue;
+        }
+        values[slot] = neighbors[i];
+        if slot_matches(&transition.slots[slot], values, slot) {
+            used[i] = true;
+            if permute_matches(transition, neighbors, values, used, slot + 1) {
+                return true;
+            }
+            used[i] = false;
+        }
+    }
+    false
+}
+
+fn transition_output(transition: &TableTransition, values: &[u8]) -> u8 {
+    match transition.output {
+        TableOutput::State(state) => state,
+        TableOutput::Slot(slot) => values[slot],
+    }
+}
+
+pub fn table_next_state(table: &Table, state: u8, neighbors: &[u8]) -> u8 {
+    if state >= table.states || neighbors.iter().any(|neighbor| *neighbor >= table.states) {
+        return state;
+    }
+    match &table.lookup {
+        TableLookup::Transitions { symmetry, permutations, transitions } => transitions
+            .iter()
+            .filter(|transition| slot_matches(&transition.slots[0], &[state], 0))
+            .find_map(|transition| {
+                let mut values = [state; 9];
+                if symmetry == &TableSymmetry::Permute {
+                    let mut used = [false; 8];
+                    return permute_matches(transition, neighbors, &mut values, &mut used, 1)
+                        .then(|| transition_output(transition, &values));
+                }
+                permutations.iter().find_map(|permutation| {
+                    for (i, p) in permutation.iter().enumerate() {
+                        values[i + 1] = neighbors[*p];
+                    }
+                    (1..=neighbors.len())
+                        .all(|i| slot_matches(&transition.slots[i], &values, i))
+                        .then(|| transition_output(transition, &values))
+                })
+            })
+            .unwrap_or(state),
+        TableLookup::Tree(nodes) => {
+            let order: &[usize] = match table.neighborhood {
+                Neighborhood::VonNeumann(_) => &VON_NEUMANN_TREE_ORDER,
+                _ => &MOORE_TREE_ORDER,
+            };
+            let mut node = nodes.len() - 1;
+            for i in order {
+                node = nodes[node][usize::from(neighbors[*i])] as usize;
+            }
+            nodes[node][usize::from(state)] as u8
+        }
+    }
+}
+
+pub fn table_into_rule(table: Table) -> Rule {
+    Rule {
+        birth: Vec::new(),
+        survival: Vec::new(),
+        generations: table.states,
+        neighborhood: table.neighborhood.clone(),
+        middle: false,
+        isotropic: None,
+        table: Some(table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InvalidHeaderErr, InvalidTransitionErr, InvalidTreeErr, InvalidVariableErr,
+        MissingTableErr, TableFromStrErr, TableSymmetry, UnsupportedNeighborhoodErr,
+        UnsupportedSymmetryErr, symmetry_permutations, table_from_str, table_into_rule,
+        table_next_state, table_try_from_str,
+    };
+    use crate::{
+        cell::{State, cell_of_table_index},
+        neighbor::Neighborhood,
+        rule::{InvalidGenerationsErr, rule_is_life_like},
+        universe::{CartesianPoint, Universe, universe_iterate},
+    };
+    use std::collections::HashMap;
+
+    const WIRE_WORLD: &str = "@RULE WireWorld
+# electrons travel along copper wires
+
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+var o={0,2,3}
+var p={o}
+var q={o}
+var r={o}
+var s={o}
+var t={o}
+var u={o}
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+
+@COLORS
+1 255 255 0
+";
+
+    const SHIFT_DOWN: &str = "@RULE ShiftDown
+@TREE
+num_states=2
+num_neighbors=4
+num_nodes=9
+1 0 0
+1 1 1
+2 0 0
+2 1 1
+3 2 2
+3 3 3
+4 4 4
+4 5 5
+5 6 7
+";
+
+    fn wire(states: [u8; 5]) -> Universe {
+        let value: HashMap<CartesianPoint, State> = states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state != 0)
+            .map(|(x, state)| (CartesianPoint::of(x as i32 - 2, 0), cell_of_table_index(*state)))
+            .collect();
+        Universe::from(value)
+    }
+
+    #[test]
+    fn test_symmetry_permutations() {
+        assert_eq!(symmetry_permutations(TableSymmetry::None, 8).len(), 1);
+        assert_eq!(symmetry_permutations(TableSymmetry::Rotate4, 8).len(), 4);
+        assert_eq!(symmetry_permutations(TableSymmetry::Rotate8, 8).len(), 8);
+        assert_eq!(symmetry_permutations(TableSymmetry::ReflectHorizontal, 8).len(), 2);
+        assert_eq!(symmetry_permutations(TableSymmetry::Rotate8Reflect, 8).len(), 16);
+        assert_eq!(symmetry_permutations(TableSymmetry::Rotate4Reflect, 4).len(), 8);
+        assert_eq!(
+            symmetry_permutations(TableSymmetry::ReflectHorizontal, 4),
+            vec![vec![0, 1, 2, 3], vec![0, 3, 2, 1]]
+        );
+    }
+
+    #[test]
+    fn test_table_try_from_str() {
+        let table = table_from_str(WIRE_WORLD);
+        assert_eq!(table.name, "WireWorld");
+        assert_eq!(table.states, 4);
+        assert_eq!(table.neighborhood, Neighborhood::Moore(1));
+        let table = table_from_str(SHIFT_DOWN);
+        assert_eq!(table.name, "ShiftDown");
+        assert_eq!(table.states, 2);
+        assert_eq!(table.neighborhood, Neighborhood::VonNeumann(1));
+        let rule = table_into_rule(table);
+        assert_eq!(rule.generations, 2);
+        assert_eq!(rule.to_string(), "ShiftDown");
+        assert!(!rule_is_life_like(&rule));
+    }
+
+    #[test]
+    fn test_table_try_from_str_err() {
+        let table = |header: &str, body: &str| format!("@RULE Test\n@TABLE\n{header}\n{body}\n");
+        let moore = "n_states:3\nneighborhood:Moore\nsymmetries:none";
+        assert_eq!(
+            table_try_from_str("@RULE Test\n@COLORS\n1 0 0 0"),
+            Err(TableFromStrErr::MissingTable(MissingTableErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table("n_states:3", "")),
+            Err(TableFromStrErr::InvalidHeader(InvalidHeaderErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table("n_states:300\nneighborhood:Moore", "")),
+            Err(TableFromStrErr::InvalidGenerations(InvalidGenerationsErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table("n_states:3\nneighborhood:hexagonal", "")),
+            Err(TableFromStrErr::UnsupportedNeighborhood(UnsupportedNeighborhoodErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(
+                "n_states:3\nneighborhood:vonNeumann\nsymmetries:rotate8",
+                ""
+            )),
+            Err(TableFromStrErr::UnsupportedSymmetry(UnsupportedSymmetryErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(moore, "var a={0,3}")),
+            Err(TableFromStrErr::InvalidVariable(InvalidVariableErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(moore, "var a=0,1")),
+            Err(TableFromStrErr::InvalidVariable(InvalidVariableErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(moore, "0,1,0,0,0,0,0,0,0")),
+            Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(moore, "0,1,0,0,0,0,0,0,z,1")),
+            Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr))
+        );
+        assert_eq!(
+            table_try_from_str(&table(moore, "0,1,0,0,0,0,0,0,0,3")),
+            Err(TableFromStrErr::InvalidTransition(InvalidTransitionErr))
+        );
+        assert_eq!(
+            table_try_from_str("@TREE\nnum_states=2\nnum_neighbors=4\nnum_nodes=2\n1 0 1\n5 0 0"),
+            Err(TableFromStrErr::InvalidTree(InvalidTreeErr))
+        );
+        assert_eq!(
+            table_try_from_str(&SHIFT_DOWN.replace("5 6 7", "5 6 8")),
+            Err(TableFromStrErr::InvalidTree(InvalidTreeErr))
+        );
+    }
+
+    #[test]
+    fn test_table_next_state() {
+        let table = table_from_str(
+            "@TABLE
+n_states:2
+neighborhood:vonNeumann
+symmetries:rotate4
+var a={0,1}
+0,1,0,0,0,1
+1,a,0,a,0,0
+011011",
+        );
+        assert_eq!(table_next_state(&table, 0, &[1, 0, 0, 0]), 1);
+        assert_eq!(table_next_state(&table, 0, &[0, 0, 0, 1]), 1);
+        assert_eq!(table_next_state(&table, 0, &[1, 1, 0, 0]), 0);
+        assert_eq!(table_next_state(&table, 1, &[1, 0, 1, 0]), 0);
+        assert_eq!(table_next_state(&table, 1, &[0, 1, 0, 1]), 0);
+        assert_eq!(table_next_state(&table, 1, &[0, 0, 0, 0]), 0);
+        assert_eq!(table_next_state(&table, 1, &[1, 0, 0, 0]), 1);
+        assert_eq!(table_next_state(&table, 0, &[1, 1, 0, 1]), 1);
+        assert_eq!(table_next_state(&table, 0, &[0, 1, 1, 1]), 1);
+        assert_eq!(table_next_state(&table, 0, &[1, 1, 1, 1]), 0);
+        let table = table_from_str(WIRE_WORLD);
+        assert_eq!(table_next_state(&table, 3, &[0, 0, 1, 0, 0, 0, 1, 0]), 1);
+        assert_eq!(table_next_state(&table, 3, &[1, 0, 1, 0, 1, 0, 0, 0]), 3);
+        assert_eq!(table_next_state(&table, 1, &[3, 3, 3, 3, 3, 3, 3, 3]), 2);
+        assert_eq!(table_next_state(&table, 3, &[7, 0, 0, 0, 0, 0, 0, 0]), 3);
+    }
+
+    #[test]
+    fn test_table_iterate() {
+        let rule = table_into_rule(table_from_str(WIRE_WORLD));
+        let mut universe = wire([3, 3, 3, 1, 2]);
+        let mut state_1 = wire([3, 3, 1, 2, 3]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe, state_1);
+        let mut state_3 = wire([1, 2, 3, 3, 3]);
+        state_3.age = 3;
+        universe_iterate(&mut universe, &rule);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe, state_3);
+        let rule = table_into_rule(table_from_str(SHIFT_DOWN));
+        let mut universe = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(1, 1)]);
+        universe_iterate(&mut universe, &rule);
+        let mut state_1 = Universe::from([CartesianPoint::of(0, -1), CartesianPoint::of(1, 0)]);
+        state_1.age = 1;
+        assert_eq!(universe, state_1);
+    }
+}
@@ -0,0 +1,417 @@
+use crate::{
+    cell::State,
+    rule::{Rule, rule_is_life_like},
+    topology::{Bounds, Topology},
+    universe::{CartesianPoint, Universe},
+};
+use manfredo::cartesian::rect::rect_i32;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+pub const TILE_LEN: i32 = 64;
+
+pub type Tile = [u64; 64];
+
+pub type TilePoint = (i32, i32);
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedRuleErr;
+
+impl fmt::Display for UnsupportedRuleErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The tile engine supports only two-state rules on the Moore neighborhood of radius 1"
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedTopologyErr;
+
+impl fmt::Display for UnsupportedTopologyErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The tile engine supports only the unbounded plane")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TileErr {
+    UnsupportedRule(UnsupportedRuleErr),
+    UnsupportedTopology(UnsupportedTopologyErr),
+}
+
+impl fmt::Display for TileErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TileErr::UnsupportedRule(err) => err.fmt(f),
+            TileErr::UnsupportedTopology(err) => err.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TileUniverse {
+    pub tiles: HashMap<TilePoint, Tile>,
+    pub age: u64,
+    birth: u16,
+    survival: u16,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TileChanges {
+    pub born: Vec<CartesianPoint>,
+    pub died: Vec<CartesianPoint>,
+}
+
+fn counts_mask(counts: &[u32]) -> u16 {
+    counts.iter().filter(|n| **n <= 8).fold(0, |mask, n| mask | (1 << n))
+}
+
+pub fn tile_universe_try_from_universe(
+    universe: &Universe,
+    rule: &Rule,
+) -> Result<TileUniverse, TileErr> {
+    if !rule_is_life_like(rule) || universe.background != State::Dead {
+        return Err(TileErr::UnsupportedRule(UnsupportedRuleErr));
+    }
+    if universe.topology != Topology::Plane {
+        return Err(TileErr::UnsupportedTopology(UnsupportedTopologyErr));
+    }
+    let mut tiles: HashMap<TilePoint, Tile> = HashMap::new();
+    for (point, state) in universe.value.iter() {
+        if state != &State::Alive {
+            continue;
+        }
+        let tile = tiles
+            .entry((point.x.div_euclid(TILE_LEN), point.y.div_euclid(TILE_LEN)))
+            .or_insert([0; 64]);
+        tile[point.y.rem_euclid(TILE_LEN) as usize] |= 1 << point.x.rem_euclid(TILE_LEN);
+    }
+    Ok(TileUniverse {
+        tiles,
+        age: universe.age,
+        birth: counts_mask(&rule.birth),
+        survival: counts_mask(&rule.survival),
+    })
+}
+
+fn tile_cells(point: &TilePoint, words: impl Iterator<Item = u64>) -> Vec<CartesianPoint> {
+    let mut cells = Vec::new();
+    for (row, word) in words.enumerate() {
+        let mut word = word;
+        while word != 0 {
+            let col = word.trailing_zeros() as i32;
+            word &= word - 1;
+            cells.push(CartesianPoint::of(
+                point.0 * TILE_LEN + col,
+                point.1 * TILE_LEN + row as i32,
+            ));
+        }
+    }
+    cells
+}
+
+pub fn tile_universe_to_universe(tile_universe: &TileUniverse) -> Universe {
+    let value = tile_universe
+        .tiles
+        .iter()
+        .flat_map(|(point, tile)| tile_cells(point, tile.iter().copied()))
+        .map(|point| (point, State::Alive))
+        .collect();
+    Universe { value, age: tile_universe.age, ..Default::default() }
+}
+
+pub fn tile_universe_to_universe_in(tile_universe: &TileUniverse, bounds: &Bounds) -> Universe {
+    let value = tile_universe
+        .tiles
+        .iter()
+        .filter(|((x, y), _)| {
+            *x >= bounds.min.x.div_euclid(TILE_LEN)
+                && *x <= bounds.max.x.div_euclid(TILE_LEN)
+                && *y >= bounds.min.y.div_euclid(TILE_LEN)
+                && *y <= bounds.max.y.div_euclid(TILE_LEN)
+        })
+        .flat_map(|(point, tile)| tile_cells(point, tile.iter().copied()))
+        .filter(|point| rect_i32::contains_point(bounds, point))
+        .map(|point| (point, State::Alive))
+        .collect();
+    Universe { value, age: tile_universe.age, ..Default::default() }
+}
+
+fn add(sum: &mut [u64; 4], value: u64) {
+    let mut carry = value;
+    for bit in sum.iter_mut() {
+        let next = *bit & carry;
+        *bit ^= carry;
+        carry = next;
+    }
+}
+
+fn count_equals(sum: &[u64; 4], n: u32) -> u64 {
+    sum.iter()
+        .enumerate()
+        .fold(!0, |acc, (i, bit)| acc & if n & (1 << i) != 0 { *bit } else { !*bit })
+}
+
+struct Surroundings<'a> {
+    tiles: [[Option<&'a Tile>; 3]; 3],
+}
+
+impl Surroundings<'_> {
+    fn word(&self, dx: usize, row: i32) -> u64 {
+        let (dy, row) = match row {
+            -1 => (0, 63),
+            64 => (2, 0),
+            _ => (1, row as usize),
+        };
+        self.tiles[dy][dx].map(|tile| tile[row]).unwrap_or(0)
+    }
+
+    fn row(&self, row: i32) -> [u64; 3] {
+        let center = self.word(1, row);
+        let west = self.word(0, row) >> 63;
+        let east = (self.word(2, row) & 1) << 63;
+        [(center << 1) | west, center, (center >> 1) | east]
+    }
+}
+
+fn tile_iterate(tile_universe: &TileUniverse, point: &TilePoint) -> Tile {
+    let mut surroundings = Surroundings { tiles: [[None; 3]; 3] };
+    for (dy, row) in surroundings.tiles.iter_mut().enumerate() {
+        for (dx, tile) in row.iter_mut().enumerate() {
+            *tile = tile_universe.tiles.get(&(point.0 + dx as i32 - 1, point.1 + dy as i32 - 1));
+        }
+    }
+    let mut next = [0; 64];
+    let mut below = surroundings.row(-1);
+    let mut current = surroundings.row(0);
+    for (row, word) in next.iter_mut().enumerate() {
+        let above = surroundings.row(row as i32 + 1);
+        let mut sum = [0; 4];
+        for value in
+            [below[0], below[1], below[2], current[0], current[2], above[0], above[1], above[2]]
+        {
+            add(&mut sum, value);
+        }
+        let alive = current[1];
+        for n in 0..=8 {
+            if tile_universe.birth & (1 << n) != 0 {
+                *word |= count_equals(&sum, n) & !alive;
+            }
+            if tile_universe.survival & (1 << n) != 0 {
+                *word |= count_equals(&sum, n) & alive;
+            }
+        }
+        below = current;
+        current = above;
+    }
+    next
+}
+
+fn active_tiles(tile_universe: &TileUniverse) -> HashSet<TilePoint> {
+    let mut active = HashSet::new();
+    for ((x, y), tile) in tile_universe.tiles.iter() {
+        let west = tile.iter().any(|word| word & 1 != 0);
+        let east = tile.iter().any(|word| word >> 63 != 0);
+        let south = tile[0] != 0;
+        let north = tile[63] != 0;
+        let south_west = tile[0] & 1 != 0;
+        let south_east = tile[0] >> 63 != 0;
+        let north_west = tile[63] & 1 != 0;
+        let north_east = tile[63] >> 63 != 0;
+        for (dx, dy, touches) in [
+            (0, 0, true),
+            (-1, 0, west),
+            (1, 0, east),
+            (0, -1, south),
+            (0, 1, north),
+            (-1, -1, south_west),
+            (1, -1, south_east),
+            (-1, 1, north_west),
+            (1, 1, north_east),
+        ] {
+            if touches {
+                active.insert((x + dx, y + dy));
+            }
+        }
+    }
+    active
+}
+
+fn next_tiles(tile_universe: &TileUniverse) -> HashMap<TilePoint, Tile> {
+    active_tiles(tile_universe)
+        .into_iter()
+        .filter_map(|point| {
+            let tile = tile_iterate(tile_universe, &point);
+            if tile.iter().any(|word| *word != 0) { Some((point, tile)) } else { None }
+        })
+        .collect()
+}
+
+pub fn tile_universe_iterate(tile_universe: &mut TileUniverse) {
+    tile_universe.tiles = next_tiles(tile_universe);
+    tile_universe.age += 1;
+}
+
+pub fn tile_universe_iterate_changes(tile_universe: &mut TileUniverse) -> TileChanges {
+    let next = next_tiles(tile_universe);
+    let previous = std::mem::replace(&mut tile_universe.tiles, next);
+    tile_universe.age += 1;
+    let mut changes = TileChanges::default();
+    for (point, old) in previous.iter() {
+        let new = tile_universe.tiles.get(point).unwrap_or(&[0; 64]);
+        changes.born.extend(tile_cells(point, new.iter().zip(old).map(|(new, old)| new & !old)));
+        changes.died.extend(tile_cells(point, new.iter().zip(old).map(|(new, old)| old & !new)));
+    }
+    for (point, new) in tile_universe.tiles.iter() {
+        if !previous.contains_key(point) {
+            changes.born.extend(tile_cells(point, new.iter().copied()));
+        }
+    }
+    changes
+}
+
+pub fn universe_iterate_tiled(universe: &mut Universe, rule: &Rule) -> Result<(), TileErr> {
+    let mut tile_universe = tile_universe_try_from_universe(universe, rule)?;
+    tile_universe_iterate(&mut tile_universe);
+    *universe = tile_universe_to_universe(&tile_universe);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TileErr, UnsupportedRuleErr, UnsupportedTopologyErr, tile_universe_iterate,
+        tile_universe_iterate_changes, tile_universe_to_universe, tile_universe_to_universe_in,
+        tile_universe_try_from_universe, universe_iterate_tiled,
+    };
+    use crate::{
+        cell::State,
+        preset::get_preset,
+        render_settings::Cam,
+        rule::{Rule, rule_from_str},
+        topology::{Bounds, Topology},
+        universe::{CartesianPoint, Universe, universe_iterate},
+    };
+
+    #[test]
+    fn tile_err() {
+        assert_eq!(
+            UnsupportedRuleErr.to_string(),
+            "The tile engine supports only two-state rules on the Moore neighborhood of radius 1"
+        );
+        assert_eq!(
+            UnsupportedTopologyErr.to_string(),
+            "The tile engine supports only the unbounded plane"
+        );
+        assert_eq!(
+            TileErr::UnsupportedRule(UnsupportedRuleErr).to_string(),
+            UnsupportedRuleErr.to_string()
+        );
+        assert_eq!(
+            tile_universe_try_from_universe(&Universe::default(), &rule_from_str("B2/S/C3")),
+            Err(TileErr::UnsupportedRule(UnsupportedRuleErr))
+        );
+        let torus =
+            Universe { topology: Topology::Torus(Bounds::of(-4, -4, 3, 3)), ..Default::default() };
+        assert_eq!(
+            tile_universe_try_from_universe(&torus, &Rule::default()),
+            Err(TileErr::UnsupportedTopology(UnsupportedTopologyErr))
+        );
+    }
+
+    #[test]
+    fn tile_universe_round_trip() {
+        for preset in ["block", "glider", "gosper_glider_gun", "puffer1"] {
+            let universe = get_preset(preset);
+            let tile_universe =
+                tile_universe_try_from_universe(&universe, &Rule::default()).unwrap();
+            assert_eq!(tile_universe_to_universe(&tile_universe), universe);
+        }
+    }
+
+    #[test]
+    fn tile_universe_across_tiles() {
+        let universe = Universe::from(
+            [(-1, -1), (-1, 0), (-1, 1), (63, 63), (64, 63), (65, 63), (63, 64), (64, 65)]
+                .map(|(x, y)| CartesianPoint::of(x, y)),
+        );
+        let mut expected = universe.clone();
+        let mut tile_universe =
+            tile_universe_try_from_universe(&universe, &Rule::default()).unwrap();
+        for _ in 0..300 {
+            universe_iterate(&mut expected, &Rule::default());
+            tile_universe_iterate(&mut tile_universe);
+            assert_eq!(tile_universe_to_universe(&tile_universe), expected);
+        }
+    }
+
+    #[test]
+    fn tile_universe_matches_universe_iterate() {
+        for rule in ["B3/S23", "B36/S23", "B1357/S1357", "B3/S012345678"] {
+            let rule = rule_from_str(rule);
+            for preset in ["r_pentomino", "gosper_glider_gun"] {
+                let mut expected = get_preset(preset);
+                let mut tile_universe = tile_universe_try_from_universe(&expected, &rule).unwrap();
+                for _ in 0..50 {
+                    universe_iterate(&mut expected, &rule);
+                    tile_universe_iterate(&mut tile_universe);
+                }
+                assert_eq!(tile_universe_to_universe(&tile_universe), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_universe_iterate_tiled() {
+        let mut universe = get_preset("glider");
+        let mut expected = universe.clone();
+        universe_iterate(&mut expected, &Rule::default());
+        assert_eq!(universe_iterate_tiled(&mut universe, &Rule::default()), Ok(()));
+        assert_eq!(universe, expected);
+    }
+
+    #[test]
+    fn tile_universe_changes() {
+        let mut expected = get_preset("gosper_glider_gun");
+        let mut tile_universe =
+            tile_universe_try_from_universe(&expected, &Rule::default()).unwrap();
+        for _ in 0..100 {
+            let mut previous = tile_universe_to_universe(&tile_universe);
+            let changes = tile_universe_iterate_changes(&mut tile_universe);
+            universe_iterate(&mut expected, &Rule::default());
+            for point in changes.born {
+                assert_eq!(previous.value.insert(point, State::Alive), None);
+            }
+            for point in changes.died {
+                assert_eq!(previous.value.remove(&point), Some(State::Alive));
+            }
+            assert_eq!(previous.value, expected.value);
+            assert_eq!(tile_universe.age, expected.age);
+        }
+    }
+
+    #[test]
+    fn tile_universe_in_bounds() {
+        let universe = Universe::from(
+            [(-1, -1), (0, 0), (63, 63), (64, 64), (200, -300)]
+                .map(|(x, y)| CartesianPoint::of(x, y)),
+        );
+        let tile_universe = tile_universe_try_from_universe(&universe, &Rule::default()).unwrap();
+        assert_eq!(
+            tile_universe_to_universe_in(&tile_universe, &Cam::of(-1, -1, 63, 63)),
+            Universe::from([(-1, -1), (0, 0), (63, 63)].map(|(x, y)| CartesianPoint::of(x, y)))
+        );
+        assert_eq!(
+            tile_universe_to_universe_in(&tile_universe, &Cam::of(0, 0, 64, 64)),
+            Universe::from([(0, 0), (63, 63), (64, 64)].map(|(x, y)| CartesianPoint::of(x, y)))
+        );
+        assert_eq!(
+            tile_universe_to_universe_in(&tile_universe, &Cam::of(-500, -500, -400, -400)),
+            Universe::default()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::universe::CartesianPoint;
+use manfredo::cartesian::rect::rect_i32;
+
+pub type Bounds = manfredo::cartesian::rect::rect_i32::Rect;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum Topology {
+    #[default]
+    Plane,
+    Bounded(Bounds),
+    Torus(Bounds),
+    KleinBottle(Bounds),
+    CrossSurface(Bounds),
+}
+
+pub fn topology_bounds(topology: &Topology) -> Option<&Bounds> {
+    match topology {
+        Topology::Plane => None,
+        Topology::Bounded(bounds) => Some(bounds),
+        Topology::Torus(bounds) => Some(bounds),
+        Topology::KleinBottle(bounds) => Some(bounds),
+        Topology::CrossSurface(bounds) => Some(bounds),
+    }
+}
+
+fn wrap(value: i32, min: i32, max: i32) -> (i32, bool) {
+    let len = max - min + 1;
+    let turns = (value - min).div_euclid(len);
+    (min + (value - min).rem_euclid(len), turns % 2 != 0)
+}
+
+fn mirror(value: i32, min: i32, max: i32) -> i32 {
+    min + max - value
+}
+
+pub fn topology_wrap(topology: &Topology, point: &CartesianPoint) -> Option<CartesianPoint> {
+    match topology {
+        Topology::Plane => Some(point.clone()),
+        Topology::Bounded(bounds) => {
+            if rect_i32::contains_point(bounds, point) {
+                Some(point.clone())
+            } else {
+                None
+            }
+        }
+        Topology::Torus(bounds) => {
+            let (x, _) = wrap(point.x, bounds.min.x, bounds.max.x);
+            let (y, _) = wrap(point.y, bounds.min.y, bounds.max.y);
+            Some(CartesianPoint::of(x, y))
+        }
+        Topology::KleinBottle(bounds) => {
+            let (y, twisted) = wrap(point.y, bounds.min.y, bounds.max.y);
+            let x = if twisted { mirror(point.x, bounds.min.x, bounds.max.x) } else { point.x };
+            let (x, _) = wrap(x, bounds.min.x, bounds.max.x);
+            Some(CartesianPoint::of(x, y))
+        }
+        Topology::CrossSurface(bounds) => {
+            let (x, twisted_x) = wrap(point.x, bounds.min.x, bounds.max.x);
+            let (y, twisted_y) = wrap(point.y, bounds.min.y, bounds.max.y);
+            let x = if twisted_y { mirror(x, bounds.min.x, bounds.max.x) } else { x };
+            let y = if twisted_x { mirror(y, bounds.min.y, bounds.max.y) } else { y };
+            Some(CartesianPoint::of(x, y))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bounds, Topology, topology_bounds, topology_wrap};
+    use crate::universe::CartesianPoint;
+
+    #[test]
+    fn test_topology_bounds() {
+        let bounds = Bounds::of(-2, -2, 1, 1);
+        assert_eq!(topology_bounds(&Topology::Plane), None);
+        assert_eq!(topology_bounds(&Topology::Bounded(bounds.clone())), Some(&bounds));
+        assert_eq!(topology_bounds(&Topology::Torus(bounds.clone())), Some(&bounds));
+        assert_eq!(topology_bounds(&Topology::KleinBottle(bounds.clone())), Some(&bounds));
+        assert_eq!(topology_bounds(&Topology::CrossSurface(bounds.clone())), Some(&bounds));
+    }
+
+    #[test]
+    fn test_topology_wrap_plane() {
+        let t = Topology::Plane;
+        assert_eq!(
+            topology_wrap(&t, &CartesianPoint::of(-100, 100)),
+            Some(CartesianPoint::of(-100, 100))
+        );
+    }
+
+    #[test]
+    fn test_topology_wrap_bounded() {
+        let t = Topology::Bounded(Bounds::of(-2, -2, 1, 1));
+        assert_eq!(
+            topology_wrap(&t, &CartesianPoint::of(-2, -2)),
+            Some(CartesianPoint::of(-2, -2))
+        );
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(1, 1)), Some(CartesianPoint::of(1, 1)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(-3, 0)), None);
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 2)), None);
+    }
+
+    #[test]
+    fn test_topology_wrap_torus() {
+        let t = Topology::Torus(Bounds::of(-2, -2, 1, 1));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 0)), Some(CartesianPoint::of(0, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(2, 0)), Some(CartesianPoint::of(-2, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(-3, 0)), Some(CartesianPoint::of(1, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 2)), Some(CartesianPoint::of(0, -2)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(2, -3)), Some(CartesianPoint::of(-2, 1)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(9, 9)), Some(CartesianPoint::of(1, 1)));
+    }
+
+    #[test]
+    fn test_topology_wrap_klein_bottle() {
+        let t = Topology::KleinBottle(Bounds::of(-2, -2, 1, 1));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 0)), Some(CartesianPoint::of(0, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(2, 0)), Some(CartesianPoint::of(-2, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(-2, 2)), Some(CartesianPoint::of(1, -2)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, -3)), Some(CartesianPoint::of(-1, 1)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 6)), Some(CartesianPoint::of(0, -2)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(2, 2)), Some(CartesianPoint::of(1, -2)));
+    }
+
+    #[test]
+    fn test_topology_wrap_cross_surface() {
+        let t = Topology::CrossSurface(Bounds::of(-2, -2, 1, 1));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, 0)), Some(CartesianPoint::of(0, 0)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(2, 0)), Some(CartesianPoint::of(-2, -1)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(-3, 1)), Some(CartesianPoint::of(1, -2)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(-2, 2)), Some(CartesianPoint::of(1, -2)));
+        assert_eq!(topology_wrap(&t, &CartesianPoint::of(0, -3)), Some(CartesianPoint::of(-1, 1)));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::universe::CartesianPoint;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipX,
+    FlipY,
+    Transpose,
+    AntiTranspose,
+}
+
+pub const TRANSFORMS: [Transform; 8] = [
+    Transform::Identity,
+    Transform::Rotate90,
+    Transform::Rotate180,
+    Transform::Rotate270,
+    Transform::FlipX,
+    Transform::FlipY,
+    Transform::Transpose,
+    Transform::AntiTranspose,
+];
+
+pub fn transform_point(transform: Transform, point: &CartesianPoint) -> CartesianPoint {
+    let (x, y) = (point.x, point.y);
+    match transform {
+        Transform::Identity => CartesianPoint::of(x, y),
+        Transform::Rotate90 => CartesianPoint::of(-y, x),
+        Transform::Rotate180 => CartesianPoint::of(-x, -y),
+        Transform::Rotate270 => CartesianPoint::of(y, -x),
+        Transform::FlipX => CartesianPoint::of(-x, y),
+        Transform::FlipY => CartesianPoint::of(x, -y),
+        Transform::Transpose => CartesianPoint::of(y, x),
+        Transform::AntiTranspose => CartesianPoint::of(-y, -x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Transform, transform_point};
+    use crate::universe::CartesianPoint;
+
+    #[test]
+    fn test_transform_point() {
+        let p = CartesianPoint::of(2, 1);
+        assert_eq!(transform_point(Transform::Identity, &p), CartesianPoint::of(2, 1));
+        assert_eq!(transform_point(Transform::Rotate90, &p), CartesianPoint::of(-1, 2));
+        assert_eq!(transform_point(Transform::Rotate180, &p), CartesianPoint::of(-2, -1));
+        assert_eq!(transform_point(Transform::Rotate270, &p), CartesianPoint::of(1, -2));
+        assert_eq!(transform_point(Transform::FlipX, &p), CartesianPoint::of(-2, 1));
+        assert_eq!(transform_point(Transform::FlipY, &p), CartesianPoint::of(2, -1));
+        assert_eq!(transform_point(Transform::Transpose, &p), CartesianPoint::of(1, 2));
+        assert_eq!(transform_point(Transform::AntiTranspose, &p), CartesianPoint::of(-1, -2));
+    }
+}
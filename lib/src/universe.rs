@@ -1,7 +1,16 @@
 use crate::{
-    cell::{State, cell_iterate, cell_toggle, cell_try_of},
-    neighbor::number_of_alive_from_model,
+    cell::{
+        State, cell_iterate, cell_iterate_configuration, cell_iterate_table, cell_toggle,
+        cell_try_of,
+    },
+    neighbor::{
+        neighbor_configuration_from_model, neighbor_states_from_model, neighborhood_offsets,
+    },
+    random::{Random, random_next_f64},
     render_settings::{Cam, RenderSettings},
+    rule::{Rule, rule_births_on_zero, rule_with_background},
+    topology::{Bounds, Topology, topology_bounds, topology_wrap},
+    transform::{Transform, transform_point},
 };
 use manfredo::{
     cartesian::rect::rect_i32,
@@ -10,6 +19,7 @@ use manfredo::{
 use std::{
     collections::{HashMap, HashSet},
     fmt,
+    ops::RangeInclusive,
 };
 
 pub type CartesianPoint = manfredo::cartesian::point::point_i32::Point;
@@ -19,6 +29,10 @@ pub type MatrixPoint = manfredo::matrix::point::point_u32::Point;
 pub struct Universe {
     pub value: HashMap<CartesianPoint, State>,
     pub age: u64,
+    pub topology: Topology,
+    pub background: State,
+    pub ages: Option<HashMap<CartesianPoint, u64>>,
+    pub background_age: u64,
 }
 
 impl From<HashMap<CartesianPoint, State>> for Universe {
@@ -69,6 +83,15 @@ pub enum FromStringErr {
     InvalidLength(InvalidLengthErr),
 }
 
+impl fmt::Display for FromStringErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromStringErr::InvalidCharacter(err) => err.fmt(f),
+            FromStringErr::InvalidLength(err) => err.fmt(f),
+        }
+    }
+}
+
 pub fn universe_try_from_string(as_str: Vec<String>) -> Result<Universe, FromStringErr> {
     if as_str.join("").find(|c| c != ' ' && cell_try_of(c).is_none()).is_some() {
         return Err(FromStringErr::InvalidCharacter(InvalidCharacterErr));
@@ -114,82 +137,772 @@ pub fn universe_from_str<const N: usize>(as_str: [&str; N]) -> Universe {
     universe_try_from_str(as_str).unwrap()
 }
 
+#[derive(Debug, PartialEq)]
+pub struct RegionTooLargeErr;
+
+impl fmt::Display for RegionTooLargeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The region must have at most {MAX_REGION_LEN} cells")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OffsetOverflowErr;
+
+impl fmt::Display for OffsetOverflowErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The pasted pattern must stay within the coordinate range")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnsupportedBackgroundErr;
+
+impl fmt::Display for UnsupportedBackgroundErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The pasted pattern must have a dead background")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OutsideTopologyErr;
+
+impl fmt::Display for OutsideTopologyErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The transformed pattern must stay within the bounds of the topology")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PasteErr {
+    RegionTooLarge(RegionTooLargeErr),
+    OffsetOverflow(OffsetOverflowErr),
+    UnsupportedBackground(UnsupportedBackgroundErr),
+}
+
+impl fmt::Display for PasteErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PasteErr::RegionTooLarge(err) => err.fmt(f),
+            PasteErr::OffsetOverflow(err) => err.fmt(f),
+            PasteErr::UnsupportedBackground(err) => err.fmt(f),
+        }
+    }
+}
+
 pub fn universe_get_value(universe: &Universe, point: &CartesianPoint) -> State {
-    if universe.value.get(point).unwrap_or(&State::Dead) == &State::Alive {
-        State::Alive
+    match topology_wrap(&universe.topology, point) {
+        Some(point) => universe.value.get(&point).copied().unwrap_or(universe.background),
+        None => State::Dead,
+    }
+}
+
+fn universe_set_value(universe: &mut Universe, point: CartesianPoint, state: State) {
+    if let Some(ages) = &mut universe.ages {
+        if state == State::Alive && universe.background == State::Alive {
+            ages.insert(point.clone(), 0);
+        } else {
+            ages.remove(&point);
+        }
+    }
+    if state == universe.background {
+        universe.value.remove(&point);
     } else {
-        State::Dead
+        universe.value.insert(point, state);
     }
 }
 
-pub fn universe_iterate(universe: &mut Universe) {
-    let points: HashSet<CartesianPoint> = universe
-        .value
-        .keys()
-        .flat_map(|point| {
-            [
-                CartesianPoint::of(point.x - 1, point.y + 1),
-                CartesianPoint::of(point.x, point.y + 1),
-                CartesianPoint::of(point.x + 1, point.y + 1),
-                CartesianPoint::of(point.x - 1, point.y),
-                point.clone(),
-                CartesianPoint::of(point.x + 1, point.y),
-                CartesianPoint::of(point.x - 1, point.y - 1),
-                CartesianPoint::of(point.x, point.y - 1),
-                CartesianPoint::of(point.x + 1, point.y - 1),
-            ]
-        })
+fn universe_update_value(universe: &mut Universe, point: CartesianPoint, state: State) {
+    if universe_get_value(universe, &point) != state {
+        universe_set_value(universe, point, state);
+    }
+}
+
+pub fn universe_set_topology(universe: &mut Universe, topology: Topology) {
+    universe.value = std::mem::take(&mut universe.value)
+        .into_iter()
+        .filter_map(|(point, state)| topology_wrap(&topology, &point).map(|point| (point, state)))
         .collect();
-    let entries: HashMap<CartesianPoint, State> = points
-        .iter()
-        .filter_map(|point| {
-            let s = universe_get_value(universe, point);
-            let number_of_alive_neighbors = number_of_alive_from_model(universe, point);
-            let new_cell = cell_iterate(s, number_of_alive_neighbors);
+    if let Some(ages) = universe.ages.take() {
+        universe.ages = Some(
+            ages.into_iter()
+                .filter_map(|(point, age)| {
+                    topology_wrap(&topology, &point).map(|point| (point, age))
+                })
+                .collect(),
+        );
+    }
+    universe.topology = topology;
+}
+
+fn universe_scatter(
+    universe: &Universe,
+    rule: &Rule,
+    offsets: &[CartesianPoint],
+    point: &CartesianPoint,
+    state: &State,
+    mut add: impl FnMut(CartesianPoint, u32),
+) {
+    let Some(point) = topology_wrap(&universe.topology, point) else {
+        return;
+    };
+    if state != &State::Alive && rule.table.is_none() {
+        add(point, 0);
+        return;
+    }
+    for offset in offsets.iter() {
+        let neighbor = CartesianPoint::of(point.x - offset.x, point.y - offset.y);
+        if let Some(neighbor) = topology_wrap(&universe.topology, &neighbor) {
+            add(neighbor, 1);
+        }
+    }
+    add(point, u32::from(rule.middle));
+}
+
+fn universe_next(
+    universe: &Universe,
+    rule: &Rule,
+    number_of_alive_neighbors: HashMap<CartesianPoint, u32>,
+) -> HashMap<CartesianPoint, State> {
+    number_of_alive_neighbors
+        .into_iter()
+        .filter_map(|(point, number_of_alive_neighbors)| {
+            let s = universe_get_value(universe, &point);
+            let new_cell = if let Some(table) = &rule.table {
+                let neighbors = neighbor_states_from_model(universe, table, &point);
+                cell_iterate_table(table, s, &neighbors)
+            } else if rule.isotropic.is_some() {
+                let configuration = neighbor_configuration_from_model(universe, &point);
+                cell_iterate_configuration(rule, s, configuration)
+            } else {
+                cell_iterate(rule, s, number_of_alive_neighbors)
+            };
             match new_cell {
                 State::Dead => None,
-                State::Alive => Some((point.clone(), State::Alive)),
+                _ => Some((point, new_cell)),
             }
         })
+        .collect()
+}
+
+fn universe_next_value_serial(universe: &Universe, rule: &Rule) -> HashMap<CartesianPoint, State> {
+    let offsets: Vec<CartesianPoint> = neighborhood_offsets(&rule.neighborhood).collect();
+    let mut number_of_alive_neighbors: HashMap<CartesianPoint, u32> = HashMap::new();
+    for (point, state) in universe.value.iter() {
+        universe_scatter(universe, rule, &offsets, point, state, |neighbor, n| {
+            *number_of_alive_neighbors.entry(neighbor).or_insert(0) += n;
+        });
+    }
+    universe_next(universe, rule, number_of_alive_neighbors)
+}
+
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_LEN: usize = 4096;
+
+#[cfg(feature = "parallel")]
+const REGION_LEN: i32 = 64;
+
+#[cfg(feature = "parallel")]
+fn universe_next_value_parallel(
+    universe: &Universe,
+    rule: &Rule,
+) -> HashMap<CartesianPoint, State> {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if threads < 2 || universe.value.len() < PARALLEL_MIN_LEN {
+        return universe_next_value_serial(universe, rule);
+    }
+    universe_next_value_regions(universe, rule, threads)
+}
+
+#[cfg(feature = "parallel")]
+fn universe_next_value_regions(
+    universe: &Universe,
+    rule: &Rule,
+    threads: usize,
+) -> HashMap<CartesianPoint, State> {
+    let region =
+        |point: &CartesianPoint| point.x.div_euclid(REGION_LEN).rem_euclid(threads as i32) as usize;
+    let offsets: Vec<CartesianPoint> = neighborhood_offsets(&rule.neighborhood).collect();
+    let cells: Vec<(&CartesianPoint, &State)> = universe.value.iter().collect();
+    let chunk_len = cells.len().div_ceil(threads);
+    let buckets: Vec<Vec<Vec<(CartesianPoint, u32)>>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = cells
+            .chunks(chunk_len)
+            .map(|chunk| {
+                let offsets = &offsets;
+                scope.spawn(move || {
+                    let mut buckets = vec![Vec::new(); threads];
+                    for (point, state) in chunk {
+                        universe_scatter(universe, rule, offsets, point, state, |neighbor, n| {
+                            buckets[region(&neighbor)].push((neighbor, n));
+                        });
+                    }
+                    buckets
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let buckets = &buckets;
+                scope.spawn(move || {
+                    let mut number_of_alive_neighbors: HashMap<CartesianPoint, u32> =
+                        HashMap::new();
+                    for (neighbor, n) in buckets.iter().flat_map(|bucket| bucket[i].iter()) {
+                        *number_of_alive_neighbors.entry(neighbor.clone()).or_insert(0) += n;
+                    }
+                    universe_next(universe, rule, number_of_alive_neighbors)
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+pub fn universe_next_ages(
+    universe: &Universe,
+    value: &HashMap<CartesianPoint, State>,
+    background: State,
+) -> Option<(HashMap<CartesianPoint, u64>, u64)> {
+    let ages = universe.ages.as_ref()?;
+    let next_age =
+        |point: &CartesianPoint| universe_get_age(universe, point).map_or(0, |age| age + 1);
+    let background_age =
+        if universe.background == State::Alive { universe.background_age + 1 } else { 0 };
+    let mut next_ages: HashMap<CartesianPoint, u64> = value
+        .iter()
+        .filter(|(_, state)| **state == State::Alive)
+        .map(|(point, _)| (point.clone(), next_age(point)))
         .collect();
+    if background == State::Alive {
+        for point in universe.value.keys().chain(ages.keys()) {
+            if !value.contains_key(point) && next_age(point) != background_age {
+                next_ages.insert(point.clone(), next_age(point));
+            }
+        }
+    }
+    Some((next_ages, background_age))
+}
+
+pub fn universe_iterate(universe: &mut Universe, rule: &Rule) {
+    let (value, background) = if universe.background != State::Dead || rule_births_on_zero(rule) {
+        universe_next_value_background(universe, rule)
+    } else {
+        #[cfg(feature = "parallel")]
+        let value = universe_next_value_parallel(universe, rule);
+        #[cfg(not(feature = "parallel"))]
+        let value = universe_next_value_serial(universe, rule);
+        (value, State::Dead)
+    };
+    if let Some((ages, background_age)) = universe_next_ages(universe, &value, background) {
+        universe.ages = Some(ages);
+        universe.background_age = background_age;
+    }
     universe.age += 1;
-    universe.value = entries;
+    universe.value = value;
+    universe.background = background;
 }
 
-pub fn universe_toggle(universe: &mut Universe, point: CartesianPoint) {
-    let new_cell = cell_toggle(&universe_get_value(universe, &point));
-    match new_cell {
-        State::Dead => {
-            universe.value.remove(&point);
+fn universe_next_value_background(
+    universe: &Universe,
+    rule: &Rule,
+) -> (HashMap<CartesianPoint, State>, State) {
+    let (rule, background) = rule_with_background(rule, universe.background);
+    let mut differences = Universe {
+        value: universe
+            .value
+            .iter()
+            .filter(|(_, state)| **state != universe.background)
+            .map(|(point, _)| (point.clone(), State::Alive))
+            .collect(),
+        topology: universe.topology.clone(),
+        ..Default::default()
+    };
+    if let Topology::Bounded(bounds) = &universe.topology
+        && universe.background != State::Dead
+    {
+        let radius = neighborhood_offsets(&rule.neighborhood)
+            .map(|offset| offset.x.unsigned_abs().max(offset.y.unsigned_abs()))
+            .max()
+            .unwrap_or(0) as i32;
+        let border = Bounds::of(
+            bounds.min.x.saturating_sub(radius),
+            bounds.min.y.saturating_sub(radius),
+            bounds.max.x.saturating_add(radius),
+            bounds.max.y.saturating_add(radius),
+        );
+        for y in border.min.y..=border.max.y {
+            let columns: Vec<i32> = if y >= bounds.min.y && y <= bounds.max.y {
+                (i64::from(border.min.x)..i64::from(bounds.min.x))
+                    .chain(i64::from(bounds.max.x) + 1..=i64::from(border.max.x))
+                    .map(|x| x as i32)
+                    .collect()
+            } else {
+                (border.min.x..=border.max.x).collect()
+            };
+            for x in columns {
+                differences.value.insert(CartesianPoint::of(x, y), State::Alive);
+            }
         }
-        State::Alive => {
-            universe.value.insert(point, new_cell);
+        differences.topology = Topology::Bounded(border);
+    }
+    universe_iterate(&mut differences, &rule);
+    let value = differences
+        .value
+        .into_keys()
+        .filter(|point| topology_wrap(&universe.topology, point).is_some())
+        .map(|point| (point, cell_toggle(&background)))
+        .collect();
+    (value, background)
+}
+
+pub fn universe_track_ages(universe: &mut Universe, enabled: bool) {
+    if !enabled {
+        universe.ages = None;
+    } else if universe.ages.is_none() {
+        universe.background_age = 0;
+        universe.ages = Some(
+            universe
+                .value
+                .iter()
+                .filter(|(_, state)| **state == State::Alive)
+                .map(|(point, _)| (point.clone(), 0))
+                .collect(),
+        );
+    }
+}
+
+pub fn universe_get_age(universe: &Universe, point: &CartesianPoint) -> Option<u64> {
+    let ages = universe.ages.as_ref()?;
+    let point = topology_wrap(&universe.topology, point)?;
+    match universe.value.get(&point) {
+        Some(State::Alive) => Some(ages.get(&point).copied().unwrap_or(0)),
+        None if universe.background == State::Alive => {
+            Some(ages.get(&point).copied().unwrap_or(universe.background_age))
         }
+        _ => None,
     }
 }
 
-pub fn universe_toggle_by_matrix_point(
+pub fn universe_iterate_n(universe: &mut Universe, rule: &Rule, n: u64) {
+    for _ in 0..n {
+        universe_iterate(universe, rule);
+    }
+}
+
+pub enum StopCondition<'a> {
+    Extinct,
+    Static,
+    Predicate(&'a dyn Fn(&Universe) -> bool),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum StopReason {
+    Completed,
+    Extinct,
+    Static,
+    Predicate,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IterateReport {
+    pub generations: u64,
+    pub reason: StopReason,
+}
+
+pub fn universe_iterate_until(
     universe: &mut Universe,
+    rule: &Rule,
+    n: u64,
+    conditions: &[StopCondition],
+) -> IterateReport {
+    let check_static =
+        conditions.iter().any(|condition| matches!(condition, StopCondition::Static));
+    for generation in 1..=n {
+        let previous =
+            if check_static { Some((universe.value.clone(), universe.background)) } else { None };
+        universe_iterate(universe, rule);
+        for condition in conditions {
+            let reason = match condition {
+                StopCondition::Extinct
+                    if universe.value.is_empty() && universe.background == State::Dead =>
+                {
+                    Some(StopReason::Extinct)
+                }
+                StopCondition::Static
+                    if previous.as_ref().is_some_and(|(value, background)| {
+                        value == &universe.value && background == &universe.background
+                    }) =>
+                {
+                    Some(StopReason::Static)
+                }
+                StopCondition::Predicate(predicate) if predicate(universe) => {
+                    Some(StopReason::Predicate)
+                }
+                _ => None,
+            };
+            if let Some(reason) = reason {
+                return IterateReport { generations: generation, reason };
+            }
+        }
+    }
+    IterateReport { generations: n, reason: StopReason::Completed }
+}
+
+fn universe_map(
+    universe: &Universe,
+    f: impl Fn(&CartesianPoint) -> CartesianPoint,
+) -> Result<Universe, OutsideTopologyErr> {
+    let map = |point: &CartesianPoint| {
+        topology_wrap(&universe.topology, &f(point)).ok_or(OutsideTopologyErr)
+    };
+    Ok(Universe {
+        value: universe
+            .value
+            .iter()
+            .map(|(point, state)| map(point).map(|point| (point, *state)))
+            .collect::<Result<_, _>>()?,
+        age: universe.age,
+        topology: universe.topology.clone(),
+        background: universe.background,
+        ages: universe
+            .ages
+            .as_ref()
+            .map(|ages| {
+                ages.iter().map(|(point, age)| map(point).map(|point| (point, *age))).collect()
+            })
+            .transpose()?,
+        background_age: universe.background_age,
+    })
+}
+
+pub fn universe_transform(
+    universe: &Universe,
+    transform: Transform,
+) -> Result<Universe, OutsideTopologyErr> {
+    universe_map(universe, |point| transform_point(transform, point))
+}
+
+pub fn universe_translate(
+    universe: &Universe,
+    offset: &CartesianPoint,
+) -> Result<Universe, OutsideTopologyErr> {
+    universe_map(universe, |point| CartesianPoint::of(point.x + offset.x, point.y + offset.y))
+}
+
+pub fn universe_toggle(universe: &mut Universe, point: CartesianPoint) {
+    let Some(point) = topology_wrap(&universe.topology, &point) else {
+        return;
+    };
+    let new_cell = cell_toggle(&universe_get_value(universe, &point));
+    universe_set_value(universe, point, new_cell);
+}
+
+pub fn universe_matrix_point_to_cartesian(
     settings: &RenderSettings,
     point: MatrixPoint,
-) {
+) -> CartesianPoint {
     let dim = f64::from(settings.dim);
     let len = rect_i32::max_len(&settings.cam) as f64;
     let cell_size = dim / len;
     let row = point.row as f64 / cell_size;
     let col = point.col as f64 / cell_size;
     let matrix_point = MatrixPoint { row: row as u32, col: col as u32 };
-    let cartesian_point = matrix_to_cartesian_in_cam(&matrix_point, &settings.cam);
-    universe_toggle(universe, cartesian_point);
+    matrix_to_cartesian_in_cam(&matrix_point, &settings.cam)
+}
+
+pub fn universe_toggle_by_matrix_point(
+    universe: &mut Universe,
+    settings: &RenderSettings,
+    point: MatrixPoint,
+) {
+    universe_toggle(universe, universe_matrix_point_to_cartesian(settings, point));
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PasteMode {
+    Or,
+    Xor,
+    And,
+    AndNot,
+    Copy,
+}
+
+fn paste_point(
+    point: &CartesianPoint,
+    offset: &CartesianPoint,
+) -> Result<CartesianPoint, PasteErr> {
+    match (point.x.checked_add(offset.x), point.y.checked_add(offset.y)) {
+        (Some(x), Some(y)) => Ok(CartesianPoint::of(x, y)),
+        _ => Err(PasteErr::OffsetOverflow(OffsetOverflowErr)),
+    }
+}
+
+fn universe_paste_region(
+    target: &Universe,
+    source: &Universe,
+    offset: &CartesianPoint,
+) -> Result<Vec<CartesianPoint>, PasteErr> {
+    let Some(bounds) = universe_bounds(source) else {
+        return Ok(Vec::new());
+    };
+    let rect =
+        Cam { min: paste_point(&bounds.min, offset)?, max: paste_point(&bounds.max, offset)? };
+    let points =
+        universe_region_points(&target.topology, &rect).map_err(PasteErr::RegionTooLarge)?;
+    Ok(points.collect())
+}
+
+pub fn universe_paste(
+    target: &mut Universe,
+    source: &Universe,
+    offset: &CartesianPoint,
+    mode: PasteMode,
+) -> Result<(), PasteErr> {
+    if source.background != State::Dead {
+        return Err(PasteErr::UnsupportedBackground(UnsupportedBackgroundErr));
+    }
+    let mut cells = HashMap::new();
+    for (point, state) in source.value.iter() {
+        if let Some(point) = topology_wrap(&target.topology, &paste_point(point, offset)?) {
+            cells.insert(point, *state);
+        }
+    }
+    let changes: Vec<(CartesianPoint, State)> = match mode {
+        PasteMode::Or => cells.into_iter().collect(),
+        PasteMode::Xor => cells
+            .into_iter()
+            .map(|(point, state)| {
+                if universe_get_value(target, &point) == State::Dead {
+                    (point, state)
+                } else {
+                    (point, State::Dead)
+                }
+            })
+            .collect(),
+        PasteMode::And => universe_paste_region(target, source, offset)?
+            .into_iter()
+            .filter(|point| !cells.contains_key(point))
+            .map(|point| (point, State::Dead))
+            .collect(),
+        PasteMode::AndNot => cells.into_keys().map(|point| (point, State::Dead)).collect(),
+        PasteMode::Copy => universe_paste_region(target, source, offset)?
+            .into_iter()
+            .map(|point| {
+                let state = cells.get(&point).copied().unwrap_or(State::Dead);
+                (point, state)
+            })
+            .collect(),
+    };
+    for (point, state) in changes {
+        universe_update_value(target, point, state);
+    }
+    Ok(())
+}
+
+pub(crate) const MAX_REGION_LEN: u64 = 1 << 22;
+
+fn rect_len(rect: &Cam) -> u64 {
+    (u64::from(rect_i32::delta_x(rect)) + 1).saturating_mul(u64::from(rect_i32::delta_y(rect)) + 1)
+}
+
+fn rect_contains_rect(outer: &Cam, inner: &Cam) -> bool {
+    rect_i32::contains_point(outer, &inner.min) && rect_i32::contains_point(outer, &inner.max)
+}
+
+fn region_tiles(min: i32, max: i32, bounds_min: i32, bounds_max: i32) -> RangeInclusive<i64> {
+    let len = i64::from(bounds_max) - i64::from(bounds_min) + 1;
+    let first = (i64::from(min) - i64::from(bounds_min)).div_euclid(len);
+    let last = (i64::from(max) - i64::from(bounds_min)).div_euclid(len);
+    first..=last.min(first + 3)
+}
+
+fn universe_region_contains(topology: &Topology, rect: &Cam, point: &CartesianPoint) -> bool {
+    if rect_i32::contains_point(rect, point) {
+        return true;
+    }
+    let Some(bounds) = topology_bounds(topology) else {
+        return false;
+    };
+    if matches!(topology, Topology::Bounded(_)) || rect_contains_rect(bounds, rect) {
+        return false;
+    }
+    let width = i64::from(bounds.max.x) - i64::from(bounds.min.x) + 1;
+    let height = i64::from(bounds.max.y) - i64::from(bounds.min.y) + 1;
+    let xs = [point.x, bounds.min.x + bounds.max.x - point.x];
+    let ys = [point.y, bounds.min.y + bounds.max.y - point.y];
+    for i in region_tiles(rect.min.x, rect.max.x, bounds.min.x, bounds.max.x) {
+        for j in region_tiles(rect.min.y, rect.max.y, bounds.min.y, bounds.max.y) {
+            for x in xs {
+                for y in ys {
+                    let (Ok(x), Ok(y)) = (
+                        i32::try_from(i64::from(x) + i * width),
+                        i32::try_from(i64::from(y) + j * height),
+                    ) else {
+                        continue;
+                    };
+                    let candidate = CartesianPoint::of(x, y);
+                    if rect_i32::contains_point(rect, &candidate)
+                        && topology_wrap(topology, &candidate).as_ref() == Some(point)
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+fn universe_region_scan(topology: &Topology, rect: &Cam) -> Option<Cam> {
+    match topology {
+        Topology::Plane => Some(rect.clone()),
+        Topology::Bounded(bounds) => {
+            let scan = Cam::of(
+                rect.min.x.max(bounds.min.x),
+                rect.min.y.max(bounds.min.y),
+                rect.max.x.min(bounds.max.x),
+                rect.max.y.min(bounds.max.y),
+            );
+            (scan.min.x <= scan.max.x && scan.min.y <= scan.max.y).then_some(scan)
+        }
+        Topology::Torus(bounds)
+        | Topology::KleinBottle(bounds)
+        | Topology::CrossSurface(bounds) => {
+            if rect_contains_rect(bounds, rect) {
+                Some(rect.clone())
+            } else {
+                Some(bounds.clone())
+            }
+        }
+    }
+}
+
+fn universe_region_points<'a>(
+    topology: &'a Topology,
+    rect: &'a Cam,
+) -> Result<impl Iterator<Item = CartesianPoint> + 'a, RegionTooLargeErr> {
+    let scan = universe_region_scan(topology, rect);
+    if scan.as_ref().is_some_and(|scan| rect_len(scan) > MAX_REGION_LEN) {
+        return Err(RegionTooLargeErr);
+    }
+    Ok(scan
+        .into_iter()
+        .flat_map(|scan| {
+            let (min_x, max_x) = (scan.min.x, scan.max.x);
+            (scan.min.y..=scan.max.y)
+                .flat_map(move |y| (min_x..=max_x).map(move |x| CartesianPoint::of(x, y)))
+        })
+        .filter(|point| universe_region_contains(topology, rect, point)))
+}
+
+pub fn universe_clear_region(universe: &mut Universe, rect: &Cam) -> Result<(), RegionTooLargeErr> {
+    if universe.background == State::Dead {
+        let topology = &universe.topology;
+        universe.value.retain(|point, _| !universe_region_contains(topology, rect, point));
+        if let Some(ages) = &mut universe.ages {
+            ages.retain(|point, _| !universe_region_contains(topology, rect, point));
+        }
+        return Ok(());
+    }
+    let topology = universe.topology.clone();
+    for point in universe_region_points(&topology, rect)? {
+        universe_update_value(universe, point, State::Dead);
+    }
+    Ok(())
+}
+
+pub fn universe_crop(universe: &mut Universe, rect: &Cam) -> Result<(), RegionTooLargeErr> {
+    if universe.background == State::Dead {
+        let topology = &universe.topology;
+        universe.value.retain(|point, _| universe_region_contains(topology, rect, point));
+        if let Some(ages) = &mut universe.ages {
+            ages.retain(|point, _| universe_region_contains(topology, rect, point));
+        }
+        return Ok(());
+    }
+    let topology = universe.topology.clone();
+    let mut value = HashMap::new();
+    let mut ages = universe.ages.as_ref().map(|_| HashMap::new());
+    for point in universe_region_points(&topology, rect)? {
+        let state = universe_get_value(universe, &point);
+        if state == State::Dead {
+            continue;
+        }
+        if let Some(ages) = &mut ages
+            && let Some(age) = universe_get_age(universe, &point)
+        {
+            ages.insert(point.clone(), age);
+        }
+        value.insert(point, state);
+    }
+    universe.value = value;
+    universe.ages = ages;
+    universe.background = State::Dead;
+    universe.background_age = 0;
+    Ok(())
+}
+
+pub fn universe_invert_region(
+    universe: &mut Universe,
+    rect: &Cam,
+) -> Result<(), RegionTooLargeErr> {
+    let topology = universe.topology.clone();
+    for point in universe_region_points(&topology, rect)? {
+        let state = cell_toggle(&universe_get_value(universe, &point));
+        universe_set_value(universe, point, state);
+    }
+    Ok(())
+}
+
+pub fn universe_fill_region(universe: &mut Universe, rect: &Cam) -> Result<(), RegionTooLargeErr> {
+    let topology = universe.topology.clone();
+    for point in universe_region_points(&topology, rect)? {
+        universe_update_value(universe, point, State::Alive);
+    }
+    Ok(())
+}
+
+pub fn universe_fill_random_region(
+    universe: &mut Universe,
+    rect: &Cam,
+    density: f64,
+    random: &mut Random,
+) -> Result<(), RegionTooLargeErr> {
+    let topology = universe.topology.clone();
+    for point in universe_region_points(&topology, rect)? {
+        let state = if random_next_f64(random) < density { State::Alive } else { State::Dead };
+        universe_update_value(universe, point, state);
+    }
+    Ok(())
 }
 
-pub fn universe_get_camera(universe: &Universe) -> Cam {
-    let all_x: Vec<i32> = universe.value.keys().map(|point| point.x).collect();
-    let all_y: Vec<i32> = universe.value.keys().map(|point| point.y).collect();
-    let mut min_x = all_x.iter().min().unwrap().to_owned();
-    let mut min_y = all_y.iter().min().unwrap().to_owned();
-    let mut max_x = all_x.iter().max().unwrap().to_owned();
-    let mut max_y = all_y.iter().max().unwrap().to_owned();
+pub fn universe_bounds(universe: &Universe) -> Option<Bounds> {
+    let mut points = universe.value.keys();
+    let first = points.next()?;
+    Some(points.fold(Bounds::of(first.x, first.y, first.x, first.y), |bounds, point| {
+        Bounds::of(
+            bounds.min.x.min(point.x),
+            bounds.min.y.min(point.y),
+            bounds.max.x.max(point.x),
+            bounds.max.y.max(point.y),
+        )
+    }))
+}
+
+pub fn universe_get_camera(universe: &Universe) -> Option<Cam> {
+    let mut all_x: Vec<i32> = universe.value.keys().map(|point| point.x).collect();
+    let mut all_y: Vec<i32> = universe.value.keys().map(|point| point.y).collect();
+    if let Some(bounds) = topology_bounds(&universe.topology) {
+        all_x.extend([bounds.min.x, bounds.max.x]);
+        all_y.extend([bounds.min.y, bounds.max.y]);
+    }
+    let mut min_x = all_x.iter().min()?.to_owned();
+    let mut min_y = all_y.iter().min()?.to_owned();
+    let mut max_x = all_x.iter().max()?.to_owned();
+    let mut max_y = all_y.iter().max()?.to_owned();
     let len_x = max_x - min_x + 1;
     let len_y = max_y - min_y + 1;
     if len_x > len_y {
@@ -206,20 +919,32 @@ pub fn universe_get_camera(universe: &Universe) -> Cam {
         min_x -= diff_start;
         max_x += diff_end;
     }
-    Cam::of(min_x - 4, min_y - 4, max_x + 4, max_y + 4)
+    Some(Cam::of(min_x - 4, min_y - 4, max_x + 4, max_y + 4))
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        CartesianPoint, FromStringErr, InvalidCharacterErr, InvalidLengthErr, MatrixPoint,
-        Universe, universe_from_str, universe_from_string, universe_get_camera, universe_iterate,
-        universe_toggle, universe_toggle_by_matrix_point, universe_try_from_str,
+        CartesianPoint, FromStringErr, InvalidCharacterErr, InvalidLengthErr, IterateReport,
+        MatrixPoint, OffsetOverflowErr, OutsideTopologyErr, PasteErr, PasteMode, RegionTooLargeErr,
+        StopCondition, StopReason, Universe, UnsupportedBackgroundErr, universe_bounds,
+        universe_clear_region, universe_crop, universe_fill_random_region, universe_fill_region,
+        universe_from_str, universe_from_string, universe_get_age, universe_get_camera,
+        universe_get_value, universe_invert_region, universe_iterate, universe_iterate_n,
+        universe_iterate_until, universe_matrix_point_to_cartesian, universe_paste,
+        universe_set_topology, universe_toggle, universe_toggle_by_matrix_point,
+        universe_track_ages, universe_transform, universe_translate, universe_try_from_str,
         universe_try_from_string,
     };
     use crate::{
         cell::State,
+        neighbor::neighborhood_from_str,
+        preset::get_preset,
+        random::random_from_seed,
         render_settings::{Cam, RenderSettings},
+        rule::{Rule, rule_from_str},
+        topology::{Bounds, Topology},
+        transform::{TRANSFORMS, Transform},
     };
     use std::collections::HashMap;
 
@@ -238,7 +963,17 @@ mod tests {
 
     #[test]
     fn test_model() {
-        assert_eq!(Universe::default(), Universe { value: HashMap::new(), age: 0 });
+        assert_eq!(
+            Universe::default(),
+            Universe {
+                value: HashMap::new(),
+                age: 0,
+                topology: Topology::Plane,
+                background: State::Dead,
+                ages: None,
+                background_age: 0,
+            }
+        );
         assert_eq!(
             Universe::from(HashMap::from([
                 (CartesianPoint::of(-1, -1), State::Alive),
@@ -254,6 +989,7 @@ mod tests {
                     (CartesianPoint::of(1, 1), State::Alive),
                 ]),
                 age: 0,
+                ..Default::default()
             }
         );
         assert_eq!(
@@ -271,6 +1007,7 @@ mod tests {
                     (CartesianPoint::of(1, 1), State::Alive),
                 ]),
                 age: 0,
+                ..Default::default()
             }
         );
         assert_eq!(
@@ -288,6 +1025,7 @@ mod tests {
                     (CartesianPoint::of(1, 1), State::Alive),
                 ]),
                 age: 0,
+                ..Default::default()
             }
         );
     }
@@ -450,6 +1188,11 @@ mod tests {
         assert_eq!(universe, universe_from_str(["⬜⬛⬛⬛", "⬛⬜⬜⬛", "⬜⬛⬛⬜", "⬛⬜⬜⬛"]));
         universe_toggle(&mut universe, CartesianPoint::of(1, 1));
         assert_eq!(universe, universe_from_str(["⬜⬛⬛⬜", "⬛⬜⬜⬛", "⬜⬛⬛⬜", "⬛⬜⬜⬛"]));
+        let mut universe = Universe { background: State::Alive, ..Default::default() };
+        universe_toggle(&mut universe, CartesianPoint::of(0, 0));
+        assert_eq!(universe.value, HashMap::from([(CartesianPoint::of(0, 0), State::Dead)]));
+        universe_toggle(&mut universe, CartesianPoint::of(0, 0));
+        assert_eq!(universe.value, HashMap::new());
     }
 
     #[test]
@@ -657,7 +1400,7 @@ mod tests {
         let mut universe = universe_from_str(["⬜"]);
         let mut state_1 = universe_from_str(["⬛"]);
         state_1.age = 1;
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_1);
     }
 
@@ -666,7 +1409,7 @@ mod tests {
         let mut universe = universe_from_str(["⬜⬜", "⬜⬜"]);
         let mut state_1 = universe_from_str(["⬜⬜", "⬜⬜"]);
         state_1.age = 1;
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_1);
     }
 
@@ -689,9 +1432,9 @@ mod tests {
             "⬛⬜⬛", //
         ]);
         state_2.age = 2;
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_1);
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_2);
     }
 
@@ -714,9 +1457,9 @@ mod tests {
             "⬛⬜⬜", //
         ]);
         state_2.age = 2;
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_1);
-        universe_iterate(&mut universe);
+        universe_iterate(&mut universe, &Rule::default());
         assert_eq!(universe, state_2);
     }
 
@@ -733,70 +1476,1064 @@ mod tests {
             "⬜⬜⬜", //
         ]);
         model3x3_5_iter1.age = 1;
-        universe_iterate(&mut model3x3_5_iter0);
+        universe_iterate(&mut model3x3_5_iter0, &Rule::default());
         assert_eq!(model3x3_5_iter0, model3x3_5_iter1);
     }
 
     #[test]
-    fn test_universe_get_camera() {
-        let preset_block = universe_from_str([
-            "⬛⬛⬛⬛⬛⬛",
-            "⬛⬛⬛⬛⬛⬛",
-            "⬛⬛⬜⬜⬛⬛",
-            "⬛⬛⬜⬜⬛⬛",
-            "⬛⬛⬛⬛⬛⬛",
-            "⬛⬛⬛⬛⬛⬛",
-        ]);
-        let preset_blinker_1 = universe_from_str([
-            "⬛⬛⬛⬛⬛",
-            "⬛⬛⬜⬛⬛",
-            "⬛⬛⬜⬛⬛",
-            "⬛⬛⬜⬛⬛",
-            "⬛⬛⬛⬛⬛",
-        ]);
-        let preset_blinker_2 = universe_from_str([
-            "⬛⬛⬛⬛⬛",
-            "⬛⬛⬛⬛⬛",
-            "⬛⬜⬜⬜⬛",
-            "⬛⬛⬛⬛⬛",
-            "⬛⬛⬛⬛⬛",
-        ]);
-        let preset_cross = universe_from_str([
+    fn universe_iterate_seeds() {
+        let mut universe = universe_from_str(["⬛⬛⬛⬛", "⬛⬛⬛⬛", "⬛⬜⬜⬛", "⬛⬛⬛⬛"]);
+        let mut state_1 = universe_from_str(["⬛⬛⬛⬛", "⬛⬜⬜⬛", "⬛⬛⬛⬛", "⬛⬜⬜⬛"]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule_from_str("B2/S"));
+        assert_eq!(universe, state_1);
+    }
+
+    #[test]
+    fn universe_iterate_high_life() {
+        let mut universe = universe_from_str([
             "⬛⬛⬛⬛⬛",
-            "⬛⬛⬜⬛⬛",
             "⬛⬜⬜⬜⬛",
+            "⬛⬜⬛⬜⬛",
             "⬛⬛⬜⬛⬛",
             "⬛⬛⬛⬛⬛",
         ]);
-        let preset_cell = universe_from_str(["⬜"]);
-        assert_eq!(universe_get_camera(&preset_block), Cam::of(-5, -5, 4, 4));
-        assert_eq!(universe_get_camera(&preset_blinker_1), Cam::of(-5, -5, 5, 5));
-        assert_eq!(universe_get_camera(&preset_blinker_2), Cam::of(-5, -5, 5, 5));
-        assert_eq!(universe_get_camera(&preset_cross), Cam::of(-5, -5, 5, 5));
-        assert_eq!(universe_get_camera(&preset_cell), Cam::of(-4, -4, 4, 4));
-        assert_eq!(
-            universe_get_camera(&Universe::from([
-                CartesianPoint::of(2, 2),
-                CartesianPoint::of(3, 5),
-                CartesianPoint::of(5, 3),
-            ])),
-            Cam::of(-2, -2, 9, 9)
-        );
+        let mut conway = universe.clone();
+        universe_iterate(&mut universe, &rule_from_str("B36/S23"));
+        universe_iterate(&mut conway, &Rule::default());
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(0, 0)), State::Alive);
+        assert_eq!(universe_get_value(&conway, &CartesianPoint::of(0, 0)), State::Dead);
+    }
+
+    #[test]
+    fn universe_iterate_brians_brain() {
+        let rule = rule_from_str("B2/S/C3");
+        let mut universe = universe_from_str(["⬛⬛⬛⬛", "⬛⬜⬛⬛", "⬛⬜⬛⬛", "⬛⬛⬛⬛"]);
+        universe_iterate(&mut universe, &rule);
         assert_eq!(
-            universe_get_camera(&Universe::from([
-                CartesianPoint::of(2, 2),
-                CartesianPoint::of(3, 4),
-                CartesianPoint::of(5, 3),
-            ])),
-            Cam::of(-2, -2, 9, 9)
+            universe,
+            Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(-1, 0), State::Dying(2)),
+                    (CartesianPoint::of(-1, -1), State::Dying(2)),
+                    (CartesianPoint::of(-2, 0), State::Alive),
+                    (CartesianPoint::of(-2, -1), State::Alive),
+                    (CartesianPoint::of(0, 0), State::Alive),
+                    (CartesianPoint::of(0, -1), State::Alive),
+                ]),
+                age: 1,
+                ..Default::default()
+            }
         );
+        universe_iterate(&mut universe, &rule);
         assert_eq!(
-            universe_get_camera(&Universe::from([
-                CartesianPoint::of(2, 2),
-                CartesianPoint::of(3, 4),
+            universe,
+            Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(-2, 0), State::Dying(2)),
+                    (CartesianPoint::of(-2, -1), State::Dying(2)),
+                    (CartesianPoint::of(0, 0), State::Dying(2)),
+                    (CartesianPoint::of(0, -1), State::Dying(2)),
+                    (CartesianPoint::of(-3, 0), State::Alive),
+                    (CartesianPoint::of(-3, -1), State::Alive),
+                    (CartesianPoint::of(1, 0), State::Alive),
+                    (CartesianPoint::of(1, -1), State::Alive),
+                    (CartesianPoint::of(-1, 1), State::Alive),
+                    (CartesianPoint::of(-1, -2), State::Alive),
+                ]),
+                age: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn universe_iterate_larger_than_life() {
+        let mut universe = universe_from_str([
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬜⬜⬛⬛",
+            "⬛⬛⬜⬜⬜⬛⬛",
+            "⬛⬛⬜⬜⬜⬛⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+        ]);
+        let mut state_1 = universe_from_str([
+            "⬛⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬜⬜⬛⬛",
+            "⬛⬜⬜⬜⬜⬜⬛",
+            "⬛⬜⬜⬜⬜⬜⬛",
+            "⬛⬜⬜⬜⬜⬜⬛",
+            "⬛⬛⬜⬜⬜⬛⬛",
+            "⬛⬛⬛⬛⬛⬛⬛",
+        ]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule_from_str("R2,C0,M1,S9,B6,NM"));
+        assert_eq!(universe, state_1);
+    }
+
+    #[test]
+    fn universe_iterate_von_neumann() {
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬛⬜⬛", "⬛⬛⬛"]);
+        let mut state_1 = universe_from_str(["⬛⬜⬛", "⬜⬛⬜", "⬛⬜⬛"]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule_from_str("R1,C0,M0,S,B1,NN"));
+        assert_eq!(universe, state_1);
+    }
+
+    #[test]
+    fn universe_iterate_hexagonal() {
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬛⬜⬛", "⬛⬛⬛"]);
+        let mut state_1 = universe_from_str(["⬜⬜⬛", "⬜⬛⬜", "⬛⬜⬜"]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule_from_str("B1/SH"));
+        assert_eq!(universe, state_1);
+    }
+
+    #[test]
+    fn universe_iterate_isotropic() {
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬜⬜⬛", "⬛⬛⬛"]);
+        let mut state_1 = universe.clone();
+        state_1.age = 1;
+        let mut totalistic = universe.clone();
+        universe_iterate(&mut universe, &rule_from_str("B2-a/S12"));
+        assert_eq!(universe, state_1);
+        universe_iterate(&mut totalistic, &rule_from_str("B2/S12"));
+        assert_ne!(totalistic, state_1);
+        let mut life = get_preset("r_pentomino");
+        let mut isotropic_life = life.clone();
+        let rule = rule_from_str("B3ceaikjqrny/S2ceaikn3ceaikjqrny");
+        for _ in 0..50 {
+            universe_iterate(&mut life, &Rule::default());
+            universe_iterate(&mut isotropic_life, &rule);
+            assert_eq!(isotropic_life, life);
+        }
+    }
+
+    #[test]
+    fn universe_iterate_birth_on_zero() {
+        let rule = rule_from_str("B0/S");
+        let mut universe = Universe::from([CartesianPoint::of(0, 0)]);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe.background, State::Alive);
+        assert_eq!(universe.value.len(), 9);
+        assert!(universe.value.values().all(|state| state == &State::Dead));
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(5, 5)), State::Alive);
+        universe_iterate(&mut universe, &rule);
+        let mut state_2 = Universe::from([CartesianPoint::of(0, 0)]);
+        state_2.age = 2;
+        assert_eq!(universe, state_2);
+    }
+
+    #[test]
+    fn universe_iterate_birth_on_zero_bounded() {
+        let bounds = Bounds::of(-3, -2, 3, 2);
+        let alive = |universe: &Universe| -> Vec<bool> {
+            (bounds.min.y..=bounds.max.y)
+                .flat_map(|y| (bounds.min.x..=bounds.max.x).map(move |x| CartesianPoint::of(x, y)))
+                .map(|point| universe_get_value(universe, &point) == State::Alive)
+                .collect()
+        };
+        let expected = |universe: &Universe, birth: &[u32], survival: &[u32]| -> Vec<bool> {
+            (bounds.min.y..=bounds.max.y)
+                .flat_map(|y| (bounds.min.x..=bounds.max.x).map(move |x| CartesianPoint::of(x, y)))
+                .map(|point| {
+                    let count = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                        .filter(|(dx, dy)| (*dx, *dy) != (0, 0))
+                        .filter(|(dx, dy)| {
+                            let neighbor = CartesianPoint::of(point.x + dx, point.y + dy);
+                            universe_get_value(universe, &neighbor) == State::Alive
+                        })
+                        .count() as u32;
+                    match universe_get_value(universe, &point) {
+                        State::Alive => survival.contains(&count),
+                        _ => birth.contains(&count),
+                    }
+                })
+                .collect()
+        };
+        let mut universe =
+            Universe { topology: Topology::Bounded(bounds.clone()), ..Default::default() };
+        let rule = rule_from_str("B0/S8");
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(alive(&universe), vec![true; 35]);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(alive(&universe).iter().filter(|alive| **alive).count(), 15);
+        universe_toggle(&mut universe, CartesianPoint::of(0, 0));
+        universe_toggle(&mut universe, CartesianPoint::of(-3, 2));
+        let rule = rule_from_str("B013/S238");
+        for _ in 0..10 {
+            let next = expected(&universe, &[0, 1, 3], &[2, 3, 8]);
+            universe_iterate(&mut universe, &rule);
+            assert_eq!(alive(&universe), next);
+        }
+    }
+
+    #[test]
+    fn universe_iterate_birth_on_zero_ages() {
+        let rule = rule_from_str("B0/S8");
+        let far = CartesianPoint::of(5, 5);
+        let center = CartesianPoint::of(0, 0);
+        let mut universe = Universe::default();
+        universe_track_ages(&mut universe, true);
+        assert_eq!(universe_get_age(&universe, &far), None);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe_get_age(&universe, &far), Some(0));
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe_get_age(&universe, &far), Some(1));
+        universe_toggle(&mut universe, center.clone());
+        assert_eq!(universe_get_age(&universe, &center), None);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe_get_age(&universe, &far), Some(2));
+        assert_eq!(universe_get_age(&universe, &center), None);
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(1, 0)), None);
+        universe_toggle(&mut universe, center.clone());
+        assert_eq!(universe_get_age(&universe, &center), Some(0));
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe_get_age(&universe, &center), None);
+
+        let mut universe = Universe {
+            topology: Topology::Bounded(Bounds::of(-1, -1, 1, 1)),
+            ..Default::default()
+        };
+        universe_track_ages(&mut universe, true);
+        universe_iterate(&mut universe, &rule);
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe_get_age(&universe, &center), Some(1));
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(1, 1)), None);
+        assert_eq!(universe_get_age(&universe, &far), None);
+    }
+
+    #[test]
+    fn universe_iterate_inverted_life() {
+        let rule = rule_from_str("B0123478/S01234678");
+        let inverted = |universe: Universe| Universe {
+            value: universe.value.into_keys().map(|point| (point, State::Dead)).collect(),
+            background: State::Alive,
+            ..universe
+        };
+        let mut universe = Universe::default();
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe, Universe { age: 1, background: State::Alive, ..Default::default() });
+        let mut universe = inverted(get_preset("r_pentomino"));
+        let mut life = get_preset("r_pentomino");
+        for _ in 0..20 {
+            universe_iterate(&mut universe, &rule);
+            universe_iterate(&mut life, &Rule::default());
+            assert_eq!(universe, inverted(life.clone()));
+        }
+    }
+
+    #[test]
+    fn universe_iterate_ages() {
+        let mut universe = get_preset("blinker");
+        let center = CartesianPoint::of(0, 0);
+        let end = CartesianPoint::of(1, 0);
+        assert_eq!(universe_get_age(&universe, &center), None);
+        universe_track_ages(&mut universe, true);
+        assert_eq!(universe_get_age(&universe, &center), Some(0));
+        universe_iterate(&mut universe, &Rule::default());
+        assert_eq!(universe_get_age(&universe, &center), Some(1));
+        assert_eq!(universe_get_age(&universe, &end), Some(0));
+        universe_iterate(&mut universe, &Rule::default());
+        assert_eq!(universe_get_age(&universe, &center), Some(2));
+        assert_eq!(universe_get_age(&universe, &end), None);
+        universe_toggle(&mut universe, center.clone());
+        universe_toggle(&mut universe, center.clone());
+        assert_eq!(universe_get_age(&universe, &center), Some(0));
+        let rotated = universe_transform(&universe, Transform::Rotate90).unwrap();
+        assert_eq!(rotated.ages.as_ref().map(|ages| ages.len()), Some(2));
+        let mut untracked = get_preset("blinker");
+        universe_iterate(&mut universe, &Rule::default());
+        universe_iterate_n(&mut untracked, &Rule::default(), 3);
+        assert_eq!(universe.value, untracked.value);
+        universe_track_ages(&mut universe, false);
+        assert_eq!(universe, untracked);
+    }
+
+    #[test]
+    fn universe_iterate_custom_neighborhood() {
+        let rule = Rule {
+            birth: vec![1],
+            survival: vec![],
+            neighborhood: neighborhood_from_str(["⬛⬜⬛", "⬛⬛⬛", "⬛⬛⬛"]),
+            ..Default::default()
+        };
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬛⬜⬛", "⬛⬛⬛"]);
+        let mut state_1 = universe_from_str(["⬛⬛⬛", "⬛⬛⬛", "⬛⬜⬛"]);
+        state_1.age = 1;
+        universe_iterate(&mut universe, &rule);
+        assert_eq!(universe, state_1);
+    }
+
+    #[test]
+    fn test_universe_get_camera() {
+        let preset_block = universe_from_str([
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬜⬛⬛",
+            "⬛⬛⬜⬜⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛⬛",
+        ]);
+        let preset_blinker_1 = universe_from_str([
+            "⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬛⬛⬛⬛",
+        ]);
+        let preset_blinker_2 = universe_from_str([
+            "⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛",
+            "⬛⬜⬜⬜⬛",
+            "⬛⬛⬛⬛⬛",
+            "⬛⬛⬛⬛⬛",
+        ]);
+        let preset_cross = universe_from_str([
+            "⬛⬛⬛⬛⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬜⬜⬜⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬛⬛⬛⬛",
+        ]);
+        let preset_cell = universe_from_str(["⬜"]);
+        assert_eq!(universe_get_camera(&preset_block), Some(Cam::of(-5, -5, 4, 4)));
+        assert_eq!(universe_get_camera(&preset_blinker_1), Some(Cam::of(-5, -5, 5, 5)));
+        assert_eq!(universe_get_camera(&preset_blinker_2), Some(Cam::of(-5, -5, 5, 5)));
+        assert_eq!(universe_get_camera(&preset_cross), Some(Cam::of(-5, -5, 5, 5)));
+        assert_eq!(universe_get_camera(&preset_cell), Some(Cam::of(-4, -4, 4, 4)));
+        assert_eq!(
+            universe_get_camera(&Universe::from([
+                CartesianPoint::of(2, 2),
+                CartesianPoint::of(3, 5),
+                CartesianPoint::of(5, 3),
+            ])),
+            Some(Cam::of(-2, -2, 9, 9))
+        );
+        assert_eq!(
+            universe_get_camera(&Universe::from([
+                CartesianPoint::of(2, 2),
+                CartesianPoint::of(3, 4),
+                CartesianPoint::of(5, 3),
+            ])),
+            Some(Cam::of(-2, -2, 9, 9))
+        );
+        assert_eq!(
+            universe_get_camera(&Universe::from([
+                CartesianPoint::of(2, 2),
+                CartesianPoint::of(3, 4),
                 CartesianPoint::of(4, 3),
             ])),
-            Cam::of(-2, -2, 8, 8)
+            Some(Cam::of(-2, -2, 8, 8))
+        );
+        assert_eq!(universe_get_camera(&Universe::default()), None);
+        let bounded = Universe {
+            topology: Topology::Bounded(Bounds::of(-2, -2, 1, 1)),
+            ..Default::default()
+        };
+        assert_eq!(universe_get_camera(&bounded), Some(Cam::of(-6, -6, 5, 5)));
+    }
+
+    #[test]
+    fn universe_iterate_bounded() {
+        let mut universe = Universe {
+            value: HashMap::from([
+                (CartesianPoint::of(-1, -1), State::Alive),
+                (CartesianPoint::of(-1, 0), State::Alive),
+                (CartesianPoint::of(-1, 1), State::Alive),
+            ]),
+            topology: Topology::Bounded(Bounds::of(-1, -1, 1, 1)),
+            ..Default::default()
+        };
+        universe_iterate(&mut universe, &Rule::default());
+        assert_eq!(
+            universe,
+            Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(-1, 0), State::Alive),
+                    (CartesianPoint::of(0, 0), State::Alive),
+                ]),
+                age: 1,
+                topology: Topology::Bounded(Bounds::of(-1, -1, 1, 1)),
+                ..Default::default()
+            }
+        );
+        universe_iterate(&mut universe, &Rule::default());
+        assert_eq!(universe.value, HashMap::new());
+    }
+
+    #[test]
+    fn universe_iterate_torus() {
+        let mut universe = universe_from_str(["⬛⬜⬛", "⬛⬛⬜", "⬜⬜⬜"]);
+        universe.topology = Topology::Torus(Bounds::of(-4, -4, 3, 3));
+        let mut state_32 = universe.clone();
+        state_32.age = 32;
+        for _ in 0..32 {
+            universe_iterate(&mut universe, &Rule::default());
+        }
+        assert_eq!(universe, state_32);
+    }
+
+    #[test]
+    fn universe_iterate_klein_bottle() {
+        let mut universe = Universe {
+            value: HashMap::from([
+                (CartesianPoint::of(-2, 1), State::Alive),
+                (CartesianPoint::of(-1, 1), State::Alive),
+                (CartesianPoint::of(0, 1), State::Alive),
+            ]),
+            topology: Topology::KleinBottle(Bounds::of(-2, -2, 1, 1)),
+            ..Default::default()
+        };
+        universe_iterate(&mut universe, &Rule::default());
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(-1, 1)), State::Alive);
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(-1, 0)), State::Alive);
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(0, -2)), State::Alive);
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(-1, -2)), State::Dead);
+        assert_eq!(universe_get_value(&universe, &CartesianPoint::of(-1, 2)), State::Alive);
+    }
+
+    #[test]
+    fn test_universe_set_topology() {
+        let mut universe = Universe::from([
+            CartesianPoint::of(0, 0),
+            CartesianPoint::of(5, 0),
+            CartesianPoint::of(0, -6),
+        ]);
+        universe_set_topology(&mut universe, Topology::Torus(Bounds::of(-2, -2, 1, 1)));
+        assert_eq!(
+            universe,
+            Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(0, 0), State::Alive),
+                    (CartesianPoint::of(1, 0), State::Alive),
+                    (CartesianPoint::of(0, -2), State::Alive),
+                ]),
+                age: 0,
+                topology: Topology::Torus(Bounds::of(-2, -2, 1, 1)),
+                ..Default::default()
+            }
+        );
+        universe_set_topology(&mut universe, Topology::Bounded(Bounds::of(0, -1, 1, 1)));
+        assert_eq!(
+            universe,
+            Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(0, 0), State::Alive),
+                    (CartesianPoint::of(1, 0), State::Alive),
+                ]),
+                age: 0,
+                topology: Topology::Bounded(Bounds::of(0, -1, 1, 1)),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn universe_toggle_topology() {
+        let mut universe = Universe {
+            topology: Topology::Bounded(Bounds::of(-1, -1, 1, 1)),
+            ..Default::default()
+        };
+        universe_toggle(&mut universe, CartesianPoint::of(2, 0));
+        assert_eq!(universe.value, HashMap::new());
+        universe.topology = Topology::Torus(Bounds::of(-1, -1, 1, 1));
+        universe_toggle(&mut universe, CartesianPoint::of(2, 0));
+        assert_eq!(universe.value, HashMap::from([(CartesianPoint::of(-1, 0), State::Alive)]));
+    }
+
+    #[test]
+    fn test_universe_bounds() {
+        assert_eq!(universe_bounds(&Universe::default()), None);
+        assert_eq!(universe_bounds(&get_preset("glider")), Some(Bounds::of(-1, -1, 1, 1)));
+        assert_eq!(
+            universe_bounds(&Universe::from([
+                CartesianPoint::of(4, -2),
+                CartesianPoint::of(-3, 7)
+            ])),
+            Some(Bounds::of(-3, -2, 4, 7))
+        );
+    }
+
+    #[test]
+    fn universe_get_camera_topology() {
+        let universe =
+            Universe { topology: Topology::Torus(Bounds::of(-5, -3, 4, 2)), ..Default::default() };
+        assert_eq!(universe_get_camera(&universe), Some(Cam::of(-9, -9, 8, 8)));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn universe_iterate_parallel() {
+        let mut seed: u64 = 42;
+        let mut value = HashMap::new();
+        for x in -80..80 {
+            for y in -80..80 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                if seed >> 62 == 0 {
+                    value.insert(CartesianPoint::of(x, y), State::Alive);
+                }
+            }
+        }
+        for rule in
+            [Rule::default(), rule_from_str("B2/S/C3"), rule_from_str("R2,C0,M1,S9..12,B7..8,NM")]
+        {
+            let mut universe = Universe::from(value.clone());
+            let mut torus = universe.clone();
+            torus.topology = Topology::Torus(Bounds::of(-80, -80, 79, 79));
+            for threads in [2, 3, 8] {
+                let parallel = super::universe_next_value_regions(&universe, &rule, threads);
+                assert_eq!(parallel, super::universe_next_value_serial(&universe, &rule));
+                let parallel = super::universe_next_value_regions(&torus, &rule, threads);
+                assert_eq!(parallel, super::universe_next_value_serial(&torus, &rule));
+            }
+            let mut serial = universe.clone();
+            for _ in 0..4 {
+                universe_iterate(&mut universe, &rule);
+                serial.value = super::universe_next_value_serial(&serial, &rule);
+                serial.age += 1;
+                assert_eq!(universe, serial);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn universe_iterate_parallel_soup() {
+        use crate::soup::{Soup, soup_generate};
+        let soup = Soup { bounds: Bounds::of(-64, -64, 63, 63), seed: 7, ..Default::default() };
+        let mut universe = soup_generate(&soup).unwrap();
+        assert!(universe.value.len() > super::PARALLEL_MIN_LEN);
+        let rule = Rule::default();
+        for _ in 0..12 {
+            let serial = super::universe_next_value_serial(&universe, &rule);
+            for threads in [2, 4, 7] {
+                assert_eq!(super::universe_next_value_regions(&universe, &rule, threads), serial);
+            }
+            assert_eq!(super::universe_next_value_parallel(&universe, &rule), serial);
+            universe_iterate(&mut universe, &rule);
+            assert_eq!(universe.value, serial);
+        }
+    }
+
+    #[test]
+    fn test_universe_iterate_n() {
+        let mut universe = universe_from_str(["⬛⬜⬛", "⬛⬛⬜", "⬜⬜⬜"]);
+        let mut expected = universe.clone();
+        for _ in 0..12 {
+            universe_iterate(&mut expected, &Rule::default());
+        }
+        universe_iterate_n(&mut universe, &Rule::default(), 12);
+        assert_eq!(universe, expected);
+        universe_iterate_n(&mut universe, &Rule::default(), 0);
+        assert_eq!(universe, expected);
+    }
+
+    #[test]
+    fn universe_iterate_until_completed() {
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬜⬜⬜", "⬛⬛⬛"]);
+        let report = universe_iterate_until(
+            &mut universe,
+            &Rule::default(),
+            5,
+            &[StopCondition::Extinct, StopCondition::Static],
+        );
+        assert_eq!(report, IterateReport { generations: 5, reason: StopReason::Completed });
+        assert_eq!(universe.age, 5);
+    }
+
+    #[test]
+    fn universe_iterate_until_extinct() {
+        let mut universe = universe_from_str(["⬛⬛⬛", "⬜⬛⬜", "⬛⬛⬛"]);
+        let report = universe_iterate_until(
+            &mut universe,
+            &Rule::default(),
+            100,
+            &[StopCondition::Static, StopCondition::Extinct],
+        );
+        assert_eq!(report, IterateReport { generations: 1, reason: StopReason::Extinct });
+        assert_eq!(universe.value, HashMap::new());
+
+        let mut universe = Universe::default();
+        let report = universe_iterate_until(
+            &mut universe,
+            &rule_from_str("B0/S"),
+            100,
+            &[StopCondition::Extinct],
+        );
+        assert_eq!(report, IterateReport { generations: 2, reason: StopReason::Extinct });
+        assert_eq!(universe.background, State::Dead);
+    }
+
+    #[test]
+    fn universe_iterate_until_static() {
+        let mut universe = universe_from_str(["⬛⬛⬛⬛", "⬛⬜⬜⬛", "⬛⬜⬛⬛", "⬛⬛⬛⬛"]);
+        let report =
+            universe_iterate_until(&mut universe, &Rule::default(), 100, &[StopCondition::Static]);
+        assert_eq!(report, IterateReport { generations: 2, reason: StopReason::Static });
+        assert_eq!(universe.value.len(), 4);
+        assert_eq!(universe.age, 2);
+
+        let mut universe = Universe::default();
+        let report = universe_iterate_until(
+            &mut universe,
+            &rule_from_str("B0/S"),
+            6,
+            &[StopCondition::Static],
+        );
+        assert_eq!(report, IterateReport { generations: 6, reason: StopReason::Completed });
+
+        let mut universe = Universe::default();
+        let report = universe_iterate_until(
+            &mut universe,
+            &rule_from_str("B0/S8"),
+            6,
+            &[StopCondition::Static],
+        );
+        assert_eq!(report, IterateReport { generations: 2, reason: StopReason::Static });
+        assert_eq!(universe.background, State::Alive);
+    }
+
+    #[test]
+    fn universe_iterate_until_predicate() {
+        let mut universe = universe_from_str(["⬛⬜⬛", "⬛⬛⬜", "⬜⬜⬜"]);
+        let far = |universe: &Universe| universe.value.keys().any(|point| point.x >= 5);
+        let report = universe_iterate_until(
+            &mut universe,
+            &Rule::default(),
+            100,
+            &[StopCondition::Extinct, StopCondition::Predicate(&far)],
+        );
+        assert_eq!(report, IterateReport { generations: 15, reason: StopReason::Predicate });
+        assert!(far(&universe));
+    }
+
+    #[test]
+    fn test_universe_transform() {
+        let universe = universe_from_str([
+            "⬛⬛⬛⬛⬛",
+            "⬛⬜⬛⬜⬛",
+            "⬛⬛⬜⬜⬛",
+            "⬛⬛⬜⬛⬛",
+            "⬛⬛⬛⬛⬛",
+        ]);
+        assert_eq!(
+            universe_transform(&universe, Transform::Rotate90),
+            Ok(universe_from_str([
+                "⬛⬛⬛⬛⬛",
+                "⬛⬜⬜⬛⬛",
+                "⬛⬛⬜⬜⬛",
+                "⬛⬜⬛⬛⬛",
+                "⬛⬛⬛⬛⬛",
+            ]))
+        );
+        assert_eq!(
+            universe_transform(&universe, Transform::FlipX),
+            Ok(universe_from_str([
+                "⬛⬛⬛⬛⬛",
+                "⬛⬜⬛⬜⬛",
+                "⬛⬜⬜⬛⬛",
+                "⬛⬛⬜⬛⬛",
+                "⬛⬛⬛⬛⬛",
+            ]))
+        );
+        let mut distinct: Vec<Universe> = Vec::new();
+        for transform in TRANSFORMS {
+            let transformed = universe_transform(&universe, transform).unwrap();
+            assert_eq!(transformed.value.len(), 5);
+            if !distinct.contains(&transformed) {
+                distinct.push(transformed);
+            }
+        }
+        assert_eq!(distinct.len(), 8);
+        let rotated = universe_transform(&universe, Transform::Rotate90).unwrap();
+        assert_eq!(
+            universe_transform(&rotated, Transform::Rotate90),
+            universe_transform(&universe, Transform::Rotate180)
+        );
+        let transposed = universe_transform(&universe, Transform::Transpose).unwrap();
+        assert_eq!(universe_transform(&transposed, Transform::Transpose), Ok(universe));
+    }
+
+    #[test]
+    fn test_universe_translate() {
+        let universe = Universe {
+            value: HashMap::from([
+                (CartesianPoint::of(0, 0), State::Alive),
+                (CartesianPoint::of(1, 0), State::Dying(1)),
+            ]),
+            age: 7,
+            ..Default::default()
+        };
+        assert_eq!(
+            universe_translate(&universe, &CartesianPoint::of(-3, 2)),
+            Ok(Universe {
+                value: HashMap::from([
+                    (CartesianPoint::of(-3, 2), State::Alive),
+                    (CartesianPoint::of(-2, 2), State::Dying(1)),
+                ]),
+                age: 7,
+                ..Default::default()
+            })
+        );
+        let torus = Universe {
+            value: HashMap::from([(CartesianPoint::of(1, 1), State::Alive)]),
+            topology: Topology::Torus(Bounds::of(-2, -2, 1, 1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            universe_translate(&torus, &CartesianPoint::of(1, 0)).map(|universe| universe.value),
+            Ok(HashMap::from([(CartesianPoint::of(-2, 1), State::Alive)]))
+        );
+        let bounded = Universe { topology: Topology::Bounded(Bounds::of(-2, -2, 1, 1)), ..torus };
+        assert_eq!(
+            universe_translate(&bounded, &CartesianPoint::of(1, 0)),
+            Err(OutsideTopologyErr)
+        );
+        assert_eq!(
+            universe_translate(&bounded, &CartesianPoint::of(0, -3)).map(|universe| universe.value),
+            Ok(HashMap::from([(CartesianPoint::of(1, -2), State::Alive)]))
+        );
+        let edge = Universe::from([CartesianPoint::of(-2, 0)]);
+        let edge = Universe { topology: bounded.topology.clone(), ..edge };
+        assert_eq!(universe_transform(&edge, Transform::Rotate180), Err(OutsideTopologyErr));
+        assert_eq!(
+            universe_transform(&edge, Transform::Rotate90).map(|universe| universe.value),
+            Ok(HashMap::from([(CartesianPoint::of(0, -2), State::Alive)]))
+        );
+    }
+
+    #[test]
+    fn test_universe_matrix_point_to_cartesian() {
+        let settings = RenderSettings { cam: Cam::of(-5, -5, 4, 4), dim: 1000, gap: 0 };
+        assert_eq!(
+            universe_matrix_point_to_cartesian(&settings, MatrixPoint { row: 0, col: 0 }),
+            CartesianPoint::of(-5, 4)
+        );
+        assert_eq!(
+            universe_matrix_point_to_cartesian(&settings, MatrixPoint { row: 999, col: 999 }),
+            CartesianPoint::of(4, -5)
+        );
+    }
+
+    #[test]
+    fn test_universe_paste() {
+        let target = universe_from_str(["⬜⬜⬛⬛", "⬜⬜⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"]);
+        let source = universe_from_str(["⬜⬛", "⬛⬜"]);
+        let offset = CartesianPoint::of(0, 1);
+        let paste = |mode| {
+            let mut universe = target.clone();
+            assert_eq!(universe_paste(&mut universe, &source, &offset, mode), Ok(()));
+            universe
+        };
+        assert_eq!(
+            paste(PasteMode::Or),
+            universe_from_str(["⬜⬜⬛⬛", "⬜⬜⬜⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"])
+        );
+        assert_eq!(
+            paste(PasteMode::Xor),
+            universe_from_str(["⬜⬛⬛⬛", "⬜⬜⬜⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"])
+        );
+        assert_eq!(
+            paste(PasteMode::And),
+            universe_from_str(["⬜⬜⬛⬛", "⬜⬛⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"])
+        );
+        assert_eq!(
+            paste(PasteMode::AndNot),
+            universe_from_str(["⬜⬛⬛⬛", "⬜⬜⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"])
+        );
+        assert_eq!(
+            paste(PasteMode::Copy),
+            universe_from_str(["⬜⬜⬛⬛", "⬜⬛⬜⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"])
+        );
+        let mut universe = target.clone();
+        assert_eq!(
+            universe_paste(&mut universe, &Universe::default(), &offset, PasteMode::Copy),
+            Ok(())
+        );
+        assert_eq!(universe, target);
+    }
+
+    #[test]
+    fn universe_paste_background() {
+        let target = Universe { background: State::Alive, ..Default::default() };
+        let source = Universe::from([CartesianPoint::of(0, 0)]);
+        let paste = |mode| {
+            let mut universe = target.clone();
+            assert_eq!(
+                universe_paste(&mut universe, &source, &CartesianPoint::of(0, 0), mode),
+                Ok(())
+            );
+            universe
+        };
+        assert_eq!(paste(PasteMode::Or), target);
+        assert_eq!(paste(PasteMode::And), target);
+        assert_eq!(paste(PasteMode::Copy), target);
+        let cleared = Universe {
+            value: HashMap::from([(CartesianPoint::of(0, 0), State::Dead)]),
+            ..target.clone()
+        };
+        assert_eq!(paste(PasteMode::Xor), cleared);
+        assert_eq!(paste(PasteMode::AndNot), cleared);
+        let mut universe = cleared.clone();
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(0, 0), PasteMode::Or),
+            Ok(())
+        );
+        assert_eq!(universe, target);
+    }
+
+    #[test]
+    fn universe_paste_ages() {
+        let mut universe = universe_from_str(["⬜⬜", "⬛⬛"]);
+        universe_track_ages(&mut universe, true);
+        universe.ages =
+            Some(HashMap::from([(CartesianPoint::of(-1, 0), 5), (CartesianPoint::of(0, 0), 5)]));
+        let source = Universe::from([CartesianPoint::of(0, 0)]);
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(0, 0), PasteMode::Or),
+            Ok(())
+        );
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(0, 0)), Some(5));
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(0, 0), PasteMode::AndNot),
+            Ok(())
+        );
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(0, 0)), None);
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(0, 0), PasteMode::Xor),
+            Ok(())
+        );
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(0, 0)), Some(0));
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(-1, 0)), Some(5));
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(-1, 0), PasteMode::Copy),
+            Ok(())
+        );
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(-1, 0)), Some(5));
+        assert_eq!(universe.ages, Some(HashMap::from([(CartesianPoint::of(-1, 0), 5)])));
+    }
+
+    #[test]
+    fn universe_paste_err() {
+        let far = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(100_000, 100_000)]);
+        let mut universe = Universe::default();
+        assert_eq!(
+            universe_paste(&mut universe, &far, &CartesianPoint::of(0, 0), PasteMode::Copy),
+            Err(PasteErr::RegionTooLarge(RegionTooLargeErr))
+        );
+        assert_eq!(
+            universe_paste(&mut universe, &far, &CartesianPoint::of(0, 0), PasteMode::Or),
+            Ok(())
+        );
+        assert_eq!(universe.value.len(), 2);
+        let source = Universe::from([CartesianPoint::of(1, 0)]);
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(i32::MAX, 0), PasteMode::Or),
+            Err(PasteErr::OffsetOverflow(OffsetOverflowErr))
+        );
+        let alive = Universe { background: State::Alive, ..Default::default() };
+        assert_eq!(
+            universe_paste(&mut universe, &alive, &CartesianPoint::of(0, 0), PasteMode::Xor),
+            Err(PasteErr::UnsupportedBackground(UnsupportedBackgroundErr))
+        );
+        assert_eq!(universe.value.len(), 2);
+        assert_eq!(
+            OffsetOverflowErr.to_string(),
+            "The pasted pattern must stay within the coordinate range"
+        );
+        assert_eq!(
+            PasteErr::UnsupportedBackground(UnsupportedBackgroundErr).to_string(),
+            "The pasted pattern must have a dead background"
+        );
+    }
+
+    #[test]
+    fn universe_paste_topology() {
+        let mut universe =
+            Universe { topology: Topology::Torus(Bounds::of(-2, -2, 1, 1)), ..Default::default() };
+        let source = Universe::from([CartesianPoint::of(0, 0), CartesianPoint::of(1, 0)]);
+        assert_eq!(
+            universe_paste(&mut universe, &source, &CartesianPoint::of(1, 0), PasteMode::Or),
+            Ok(())
+        );
+        assert_eq!(
+            universe.value,
+            HashMap::from([
+                (CartesianPoint::of(1, 0), State::Alive),
+                (CartesianPoint::of(-2, 0), State::Alive),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_universe_region() {
+        let universe = universe_from_str(["⬜⬜⬛⬛", "⬜⬜⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"]);
+        let rect = Cam::of(-1, -1, 0, 0);
+        let mut cleared = universe.clone();
+        assert_eq!(universe_clear_region(&mut cleared, &rect), Ok(()));
+        assert_eq!(cleared, universe_from_str(["⬜⬜⬛⬛", "⬜⬛⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"]));
+        let mut cropped = universe.clone();
+        assert_eq!(universe_crop(&mut cropped, &rect), Ok(()));
+        assert_eq!(cropped, universe_from_str(["⬛⬛⬛⬛", "⬛⬜⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬛"]));
+        let mut inverted = universe.clone();
+        assert_eq!(universe_invert_region(&mut inverted, &rect), Ok(()));
+        assert_eq!(inverted, universe_from_str(["⬜⬜⬛⬛", "⬜⬛⬜⬛", "⬛⬜⬜⬛", "⬛⬛⬛⬜"]));
+        let mut filled = universe.clone();
+        assert_eq!(universe_fill_region(&mut filled, &rect), Ok(()));
+        assert_eq!(filled, universe_from_str(["⬜⬜⬛⬛", "⬜⬜⬜⬛", "⬛⬜⬜⬛", "⬛⬛⬛⬜"]));
+    }
+
+    #[test]
+    fn universe_region_ages() {
+        let mut universe = universe_from_str(["⬜⬜⬛⬛", "⬜⬜⬛⬛", "⬛⬛⬛⬛", "⬛⬛⬛⬜"]);
+        universe_track_ages(&mut universe, true);
+        let ages = universe.ages.clone().unwrap();
+        let mut cropped = universe.clone();
+        assert_eq!(universe_crop(&mut cropped, &Cam::of(-2, -1, -1, 1)), Ok(()));
+        assert_eq!(cropped.value.len(), 4);
+        assert_eq!(cropped.ages.as_ref().map(HashMap::len), Some(4));
+        let mut cleared = universe.clone();
+        assert_eq!(universe_clear_region(&mut cleared, &Cam::of(-2, -1, -1, 1)), Ok(()));
+        assert_eq!(cleared.ages, Some(HashMap::from([(CartesianPoint::of(1, -2), 0)])));
+        let mut filled = universe.clone();
+        filled.ages = Some(ages.keys().map(|point| (point.clone(), 3)).collect());
+        assert_eq!(universe_fill_region(&mut filled, &Cam::of(-2, 0, 0, 1)), Ok(()));
+        assert_eq!(universe_get_age(&filled, &CartesianPoint::of(-2, 1)), Some(3));
+        assert_eq!(universe_get_age(&filled, &CartesianPoint::of(0, 1)), Some(0));
+    }
+
+    #[test]
+    fn universe_region_topology() {
+        let torus = Topology::Torus(Bounds::of(-2, -2, 1, 1));
+        let mut universe = Universe {
+            value: HashMap::from([
+                (CartesianPoint::of(-2, 0), State::Alive),
+                (CartesianPoint::of(1, 0), State::Alive),
+                (CartesianPoint::of(0, 0), State::Alive),
+            ]),
+            topology: torus.clone(),
+            ..Default::default()
+        };
+        let rect = Cam::of(1, 0, 2, 0);
+        let mut cropped = universe.clone();
+        assert_eq!(universe_crop(&mut cropped, &rect), Ok(()));
+        assert_eq!(
+            cropped.value,
+            HashMap::from([
+                (CartesianPoint::of(-2, 0), State::Alive),
+                (CartesianPoint::of(1, 0), State::Alive),
+            ])
+        );
+        let mut cleared = universe.clone();
+        assert_eq!(universe_clear_region(&mut cleared, &rect), Ok(()));
+        assert_eq!(cleared.value, HashMap::from([(CartesianPoint::of(0, 0), State::Alive)]));
+        assert_eq!(universe_invert_region(&mut universe, &rect), Ok(()));
+        assert_eq!(universe.value, HashMap::from([(CartesianPoint::of(0, 0), State::Alive)]));
+
+        let mut klein = Universe {
+            value: HashMap::from([(CartesianPoint::of(1, -2), State::Alive)]),
+            topology: Topology::KleinBottle(Bounds::of(-2, -2, 1, 1)),
+            ..Default::default()
+        };
+        let mut cropped = klein.clone();
+        assert_eq!(universe_crop(&mut cropped, &Cam::of(-2, 2, -2, 2)), Ok(()));
+        assert_eq!(cropped, klein);
+        assert_eq!(universe_crop(&mut klein, &Cam::of(1, 2, 1, 2)), Ok(()));
+        assert_eq!(klein.value, HashMap::new());
+
+        let mut bounded = Universe {
+            topology: Topology::Bounded(Bounds::of(-2, -2, 1, 1)),
+            ..Default::default()
+        };
+        assert_eq!(universe_fill_region(&mut bounded, &Cam::of(0, 0, 1000, 1000)), Ok(()));
+        assert_eq!(bounded.value.len(), 4);
+    }
+
+    #[test]
+    fn universe_crop_background() {
+        let mut universe = Universe {
+            value: HashMap::from([(CartesianPoint::of(0, 0), State::Dead)]),
+            background: State::Alive,
+            ..Default::default()
+        };
+        universe_track_ages(&mut universe, true);
+        assert_eq!(universe_crop(&mut universe, &Cam::of(0, 0, 1, 1)), Ok(()));
+        assert_eq!(universe.background, State::Dead);
+        assert_eq!(
+            universe.value,
+            HashMap::from([
+                (CartesianPoint::of(1, 0), State::Alive),
+                (CartesianPoint::of(0, 1), State::Alive),
+                (CartesianPoint::of(1, 1), State::Alive),
+            ])
+        );
+        assert_eq!(universe_get_age(&universe, &CartesianPoint::of(1, 1)), Some(0));
+        let mut universe = Universe { background: State::Alive, ..Default::default() };
+        assert_eq!(
+            universe_crop(&mut universe, &Cam::of(i32::MIN, i32::MIN, i32::MAX, i32::MAX)),
+            Err(RegionTooLargeErr)
+        );
+    }
+
+    #[test]
+    fn universe_region_too_large() {
+        assert_eq!(RegionTooLargeErr.to_string(), "The region must have at most 4194304 cells");
+        let rect = Cam::of(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+        let mut universe = universe_from_str(["⬜⬜", "⬜⬛"]);
+        assert_eq!(universe_invert_region(&mut universe, &rect), Err(RegionTooLargeErr));
+        assert_eq!(universe_fill_region(&mut universe, &rect), Err(RegionTooLargeErr));
+        assert_eq!(
+            universe_fill_random_region(&mut universe, &rect, 0.5, &mut random_from_seed(1)),
+            Err(RegionTooLargeErr)
+        );
+        assert_eq!(universe.value.len(), 3);
+        let mut cropped = universe.clone();
+        assert_eq!(universe_crop(&mut cropped, &rect), Ok(()));
+        assert_eq!(cropped, universe);
+        assert_eq!(universe_clear_region(&mut universe, &rect), Ok(()));
+        assert_eq!(universe.value, HashMap::new());
+        let mut inverted = Universe { background: State::Alive, ..Default::default() };
+        assert_eq!(universe_clear_region(&mut inverted, &rect), Err(RegionTooLargeErr));
+        let mut torus =
+            Universe { topology: Topology::Torus(Bounds::of(-2, -2, 1, 1)), ..Default::default() };
+        assert_eq!(universe_fill_region(&mut torus, &rect), Ok(()));
+        assert_eq!(torus.value.len(), 16);
+    }
+
+    #[test]
+    fn test_universe_fill_random_region() {
+        let rect = Cam::of(-50, -50, 49, 49);
+        let mut a = Universe::from([CartesianPoint::of(100, 100)]);
+        let mut b = a.clone();
+        assert_eq!(
+            universe_fill_random_region(&mut a, &rect, 0.3, &mut random_from_seed(7)),
+            Ok(())
+        );
+        assert_eq!(
+            universe_fill_random_region(&mut b, &rect, 0.3, &mut random_from_seed(7)),
+            Ok(())
+        );
+        assert_eq!(a, b);
+        assert!(a.value.contains_key(&CartesianPoint::of(100, 100)));
+        let population = a.value.len() - 1;
+        assert!((2700..3300).contains(&population));
+        assert_eq!(
+            universe_fill_random_region(&mut b, &rect, 0.3, &mut random_from_seed(8)),
+            Ok(())
+        );
+        assert_ne!(a, b);
+        assert_eq!(
+            universe_fill_random_region(&mut b, &rect, 0.0, &mut random_from_seed(8)),
+            Ok(())
+        );
+        assert_eq!(b.value.len(), 1);
+        assert_eq!(
+            universe_fill_random_region(&mut b, &rect, 1.0, &mut random_from_seed(8)),
+            Ok(())
         );
+        assert_eq!(b.value.len(), 10001);
     }
 }
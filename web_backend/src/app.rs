@@ -3,12 +3,28 @@ use std::cell::RefCell;
 use web_sys::CanvasRenderingContext2d;
 
 use libre_game_of_life_lib::{
+    cell::State,
+    heat::{Heat, HeatMetric, heat_accumulate, heat_clear},
+    history::{
+        Delta, History, history_apply_delta, history_push, history_push_delta, history_step_back,
+    },
+    pattern::{PatternFormat, PatternFromStrErr, pattern_format_detect, pattern_try_from_str},
     preset::{Preset, get_preset, get_preset_groups, try_get_preset},
-    render::{Renderable, get_values_to_render},
-    render_settings::RenderSettings,
+    random::random_from_seed,
+    render::{Renderable, get_boundary_to_render, get_heat_to_render, get_values_to_render},
+    render_settings::{Cam, RenderSettings},
+    rle::{Rle, RleFromStrErr, rle_to_string, rle_try_from_str},
+    rule::{Rule, RuleFromStrErr, rule_try_from_str},
+    soup::{NonSquareBoundsErr, SOUP_PRESET_ID, Soup, soup_generate, soup_try_bounds},
+    table::{TableFromStrErr, table_into_rule, table_try_from_str},
+    tile::{TileUniverse, tile_universe_iterate_changes, tile_universe_try_from_universe},
+    topology::Topology,
     universe::{
-        CartesianPoint, MatrixPoint, Universe, universe_get_camera, universe_iterate,
-        universe_toggle, universe_toggle_by_matrix_point,
+        CartesianPoint, MatrixPoint, PasteErr, PasteMode, RegionTooLargeErr, Universe,
+        universe_clear_region, universe_crop, universe_fill_random_region, universe_fill_region,
+        universe_get_camera, universe_invert_region, universe_iterate,
+        universe_matrix_point_to_cartesian, universe_paste, universe_set_topology, universe_toggle,
+        universe_toggle_by_matrix_point, universe_track_ages,
     },
 };
 
@@ -49,6 +65,14 @@ pub fn build_preset_option_groups() -> Vec<PresetOptionGroup> {
                 .map(|item| PresetOptionItem { label: item.name, value: item.id })
                 .collect(),
         })
+        .chain([PresetOptionGroup {
+            label: "Random".into(),
+            value: SOUP_PRESET_ID.into(),
+            options: vec![PresetOptionItem {
+                label: "Random soup".into(),
+                value: SOUP_PRESET_ID.into(),
+            }],
+        }])
         .collect()
 }
 
@@ -90,6 +114,13 @@ pub struct AppSettings {
 
 pub struct Model {
     pub universe: Universe,
+    pub tiles: Option<TileUniverse>,
+    pub rule: Rule,
+    pub history: History,
+    pub soup: Soup,
+    pub age_tracking: bool,
+    pub heat: Option<Heat>,
+    pub heat_metric: HeatMetric,
     pub settings: AppSettings,
     pub holder: Option<Holder>,
 }
@@ -97,9 +128,16 @@ pub struct Model {
 impl Default for Model {
     fn default() -> Self {
         let universe = get_preset("block");
-        let cam = universe_get_camera(&universe);
+        let cam = universe_get_camera(&universe).unwrap_or(Cam::of(-5, -5, 4, 4));
         Model {
             universe,
+            tiles: None,
+            rule: Rule::default(),
+            history: History::default(),
+            soup: Soup::default(),
+            age_tracking: false,
+            heat: None,
+            heat_metric: HeatMetric::Alive,
             settings: AppSettings {
                 preset: Some("block".into()),
                 fps: 4,
@@ -128,6 +166,8 @@ pub enum Prop {
     Status,
     Dim,
     Cam,
+    Rule,
+    Topology,
 }
 
 pub fn add_on_change_listener<F>(cb: F)
@@ -150,12 +190,50 @@ fn fps_to_mili(fps: u16) -> u16 {
 }
 
 const DEAD_COLOR: &str = "#dbdbdb";
+const OUTSIDE_COLOR: &str = "#a8a8a8";
 const ALIVE_COLOR: &str = "#2e2e2e";
+const DEAD_SHADE: u16 = 0xdb;
+const ALIVE_SHADE: u16 = 0x2e;
+const YOUNG_COLOR: (u64, u64, u64) = (0xf5, 0xa6, 0x23);
+const AGE_SPAN: u64 = 64;
+const HEAT_COLOR: (u8, u8, u8) = (0xe6, 0x39, 0x46);
+const HEAT_MAX_ALPHA: f64 = 0.8;
+const TABLE_COLORS: [&str; 6] = ["#1d6fd8", "#e63946", "#f4a261", "#2a9d8f", "#8338ec", "#ffbe0b"];
+
+fn state_color(state: &State, generations: u8) -> String {
+    match state {
+        State::Dead => DEAD_COLOR.into(),
+        State::Alive => ALIVE_COLOR.into(),
+        State::Dying(index) => {
+            let step = (DEAD_SHADE - ALIVE_SHADE) * u16::from(*index - 1) / u16::from(generations);
+            let shade = ALIVE_SHADE + step;
+            format!("#{shade:02x}{shade:02x}{shade:02x}")
+        }
+        State::Other(index) => TABLE_COLORS[usize::from(*index - 2) % TABLE_COLORS.len()].into(),
+    }
+}
+
+fn age_color(age: u64) -> String {
+    let age = age.min(AGE_SPAN);
+    let blend = |young: u64| (young * (AGE_SPAN - age) + u64::from(ALIVE_SHADE) * age) / AGE_SPAN;
+    format!("#{:02x}{:02x}{:02x}", blend(YOUNG_COLOR.0), blend(YOUNG_COLOR.1), blend(YOUNG_COLOR.2))
+}
+
+fn heat_color(intensity: f64) -> String {
+    let alpha = intensity.clamp(0.0, 1.0) * HEAT_MAX_ALPHA;
+    format!("rgba({}, {}, {}, {alpha:.2})", HEAT_COLOR.0, HEAT_COLOR.1, HEAT_COLOR.2)
+}
 
 fn render() {
-    let (universe, settings, holder) = MODEL.with(|m| {
+    let (universe, generations, settings, holder, heat) = MODEL.with(|m| {
         let model = m.borrow();
-        (model.universe.clone(), model.settings.clone(), model.holder.clone())
+        (
+            model.universe.clone(),
+            model.rule.generations,
+            model.settings.clone(),
+            model.holder.clone(),
+            model.heat.clone().map(|heat| (heat, model.heat_metric)),
+        )
     });
     if settings.render_settings.dim == 0 {
         return;
@@ -167,10 +245,26 @@ fn render() {
             f64::from(settings.render_settings.dim),
             f64::from(settings.render_settings.dim),
         );
-        holder.draw_square(bg, DEAD_COLOR.into());
+        let background = state_color(&universe.background, generations);
+        match get_boundary_to_render(&universe, &settings.render_settings) {
+            Some(boundary) => {
+                holder.draw_square(bg, OUTSIDE_COLOR.into());
+                holder.draw_square(boundary, background);
+            }
+            None => holder.draw_square(bg, background),
+        }
         let values_to_render = get_values_to_render(&universe, &settings.render_settings);
-        for sq in values_to_render {
-            holder.draw_square(sq, ALIVE_COLOR.into());
+        for value in values_to_render {
+            let color = match value.age {
+                Some(age) => age_color(age),
+                None => state_color(&value.state, generations),
+            };
+            holder.draw_square(value.rect, color);
+        }
+        if let Some((heat, metric)) = heat {
+            for value in get_heat_to_render(&heat, metric, &settings.render_settings) {
+                holder.draw_square(value.rect, heat_color(value.intensity));
+            }
         }
     }
 }
@@ -260,12 +354,24 @@ pub fn app_set_fps(fps: u16) {
 }
 
 pub fn app_set_preset(preset: String) {
-    if let Some(selected_preset) = try_get_preset(&preset) {
+    let selected_preset = if preset == SOUP_PRESET_ID {
+        MODEL.with(|m| soup_generate(&m.borrow().soup).ok())
+    } else {
+        try_get_preset(&preset)
+    };
+    if let Some(mut selected_preset) = selected_preset {
         MODEL.with(|m| {
             let mut model = m.borrow_mut();
-            model.settings.render_settings.cam = universe_get_camera(&selected_preset);
+            universe_set_topology(&mut selected_preset, model.universe.topology.clone());
+            if let Some(cam) = universe_get_camera(&selected_preset) {
+                model.settings.render_settings.cam = cam;
+            }
             model.universe = selected_preset;
+            model.tiles = None;
             model.settings.preset = Some(preset);
+            if let Some(heat) = &mut model.heat {
+                heat_clear(heat);
+            }
         });
         on_change(Prop::Universe);
         on_change(Prop::Preset);
@@ -273,21 +379,188 @@ pub fn app_set_preset(preset: String) {
     }
 }
 
+pub fn app_set_soup(soup: Soup) -> Result<(), NonSquareBoundsErr> {
+    soup_try_bounds(&soup)?;
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        model.soup = soup;
+    });
+    Ok(())
+}
+
+pub fn app_set_rule(rule: String) -> Result<(), RuleFromStrErr> {
+    let rule = rule_try_from_str(&rule)?;
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        model.rule = rule;
+        model.tiles = None;
+    });
+    on_change(Prop::Rule);
+    Ok(())
+}
+
+pub fn app_set_rule_table(table: String) -> Result<(), TableFromStrErr> {
+    let rule = table_into_rule(table_try_from_str(&table)?);
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        model.rule = rule;
+        model.tiles = None;
+    });
+    on_change(Prop::Rule);
+    Ok(())
+}
+
+fn load_universe(mut universe: Universe, rule: Option<Rule>) {
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        universe_set_topology(&mut universe, model.universe.topology.clone());
+        universe_track_ages(&mut universe, model.age_tracking);
+        if let Some(cam) = universe_get_camera(&universe) {
+            model.settings.render_settings.cam = cam;
+        }
+        model.universe = universe;
+        model.tiles = None;
+        model.settings.preset = None;
+        if let Some(rule) = rule {
+            model.rule = rule;
+        }
+        if let Some(heat) = &mut model.heat {
+            heat_clear(heat);
+        }
+    });
+    on_change(Prop::Universe);
+    on_change(Prop::Preset);
+    on_change(Prop::Cam);
+    on_change(Prop::Rule);
+}
+
+pub fn app_load_rle(rle: String) -> Result<(), RleFromStrErr> {
+    let Rle { rule, universe, .. } = rle_try_from_str(&rle)?;
+    load_universe(universe, rule);
+    Ok(())
+}
+
+pub fn app_load_pattern(pattern: String) -> Result<(), PatternFromStrErr> {
+    if pattern_format_detect(&pattern) == Some(PatternFormat::Rle) {
+        return app_load_rle(pattern).map_err(PatternFromStrErr::Rle);
+    }
+    load_universe(pattern_try_from_str(&pattern)?, None);
+    Ok(())
+}
+
+pub fn app_save_rle() -> String {
+    MODEL.with(|m| {
+        let model = m.borrow();
+        rle_to_string(&Rle {
+            rule: Some(model.rule.clone()),
+            universe: model.universe.clone(),
+            ..Default::default()
+        })
+    })
+}
+
+pub fn app_set_age_tracking(enabled: bool) {
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        model.age_tracking = enabled;
+        universe_track_ages(&mut model.universe, enabled);
+    });
+    on_change(Prop::Universe);
+}
+
+pub fn app_set_heat_map(enabled: bool, metric: HeatMetric, decay: f64) {
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        if enabled {
+            model.heat.get_or_insert_with(Heat::default).decay = decay.clamp(0.0, 1.0);
+            model.heat_metric = metric;
+        } else {
+            model.heat = None;
+        }
+    });
+    on_change(Prop::Universe);
+}
+
+pub fn app_set_topology(topology: Topology) {
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        universe_set_topology(&mut model.universe, topology);
+        model.tiles = None;
+    });
+    on_change(Prop::Universe);
+    on_change(Prop::Topology);
+}
+
+fn iterate_tiled(model: &mut Model) -> bool {
+    if model.age_tracking || model.heat.is_some() {
+        model.tiles = None;
+        return false;
+    }
+    if model.tiles.is_none() {
+        model.tiles = tile_universe_try_from_universe(&model.universe, &model.rule).ok();
+    }
+    let Some(tiles) = &mut model.tiles else {
+        return false;
+    };
+    let changes = tile_universe_iterate_changes(tiles);
+    let delta = Delta {
+        changed: changes.born.into_iter().map(|p| (p, State::Alive)).collect(),
+        removed: changes.died,
+        background: State::Dead,
+    };
+    history_apply_delta(&mut model.universe, &delta);
+    model.universe.age = tiles.age;
+    history_push_delta(&mut model.history, &model.universe, delta);
+    true
+}
+
+fn iterate(model: &mut Model) {
+    if model.tiles.is_none() {
+        history_push(&mut model.history, &model.universe);
+    }
+    if iterate_tiled(model) {
+        return;
+    }
+    universe_track_ages(&mut model.universe, model.age_tracking);
+    let previous = model.heat.as_ref().map(|_| model.universe.clone());
+    universe_iterate(&mut model.universe, &model.rule);
+    if let (Some(heat), Some(previous)) = (&mut model.heat, previous) {
+        heat_accumulate(heat, &previous, &model.universe);
+    }
+    history_push(&mut model.history, &model.universe);
+}
+
 pub fn app_single_iteration() {
     MODEL.with(|m| {
         let mut model = m.borrow_mut();
+        let model = &mut *model;
         model.settings.status = Status::Paused;
-        universe_iterate(&mut model.universe);
+        iterate(model);
     });
     on_change(Prop::Status);
     on_change(Prop::Universe);
 }
 
-pub fn app_iterate() {
-    MODEL.with(|m| {
+pub fn app_step_back() {
+    let stepped = MODEL.with(|m| {
         let mut model = m.borrow_mut();
-        universe_iterate(&mut model.universe);
+        let model = &mut *model;
+        model.settings.status = Status::Paused;
+        model.tiles = None;
+        let stepped = history_step_back(&model.history, &mut model.universe);
+        if stepped {
+            universe_track_ages(&mut model.universe, model.age_tracking);
+        }
+        stepped
     });
+    on_change(Prop::Status);
+    if stepped {
+        on_change(Prop::Universe);
+    }
+}
+
+pub fn app_iterate() {
+    MODEL.with(|m| iterate(&mut m.borrow_mut()));
     on_change(Prop::Universe);
 }
 
@@ -295,6 +568,7 @@ pub fn app_toggle_by_point(p: CartesianPoint) {
     MODEL.with(|m| {
         let mut model = m.borrow_mut();
         universe_toggle(&mut model.universe, p);
+        model.tiles = None;
         model.settings.preset = None;
     });
     on_change(Prop::Universe);
@@ -306,12 +580,87 @@ pub fn app_toggle_model_cell_by_absolute_point(p: MatrixPoint) {
         let mut model = m.borrow_mut();
         let render_settings = model.settings.render_settings.clone();
         universe_toggle_by_matrix_point(&mut model.universe, &render_settings, p);
+        model.tiles = None;
         model.settings.preset = None;
     });
     on_change(Prop::Universe);
     on_change(Prop::Preset);
 }
 
+pub fn app_paste_preset_at_point(
+    preset: String,
+    p: CartesianPoint,
+    mode: PasteMode,
+) -> Result<(), PasteErr> {
+    let Some(selected_preset) = try_get_preset(&preset) else {
+        return Ok(());
+    };
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        universe_paste(&mut model.universe, &selected_preset, &p, mode)?;
+        model.tiles = None;
+        model.settings.preset = None;
+        Ok(())
+    })?;
+    on_change(Prop::Universe);
+    on_change(Prop::Preset);
+    Ok(())
+}
+
+pub fn app_paste_preset_by_absolute_point(
+    preset: String,
+    p: MatrixPoint,
+    mode: PasteMode,
+) -> Result<(), PasteErr> {
+    let render_settings = MODEL.with(|m| m.borrow().settings.render_settings.clone());
+    let p = universe_matrix_point_to_cartesian(&render_settings, p);
+    app_paste_preset_at_point(preset, p, mode)
+}
+
+pub fn app_region_by_absolute_points(a: MatrixPoint, b: MatrixPoint) -> Cam {
+    let render_settings = MODEL.with(|m| m.borrow().settings.render_settings.clone());
+    let a = universe_matrix_point_to_cartesian(&render_settings, a);
+    let b = universe_matrix_point_to_cartesian(&render_settings, b);
+    Cam::of(a.x.min(b.x), a.y.min(b.y), a.x.max(b.x), a.y.max(b.y))
+}
+
+fn edit_region(
+    f: impl FnOnce(&mut Universe) -> Result<(), RegionTooLargeErr>,
+) -> Result<(), RegionTooLargeErr> {
+    MODEL.with(|m| {
+        let mut model = m.borrow_mut();
+        f(&mut model.universe)?;
+        model.tiles = None;
+        model.settings.preset = None;
+        Ok(())
+    })?;
+    on_change(Prop::Universe);
+    on_change(Prop::Preset);
+    Ok(())
+}
+
+pub fn app_clear_region(rect: Cam) -> Result<(), RegionTooLargeErr> {
+    edit_region(|universe| universe_clear_region(universe, &rect))
+}
+
+pub fn app_crop(rect: Cam) -> Result<(), RegionTooLargeErr> {
+    edit_region(|universe| universe_crop(universe, &rect))
+}
+
+pub fn app_invert_region(rect: Cam) -> Result<(), RegionTooLargeErr> {
+    edit_region(|universe| universe_invert_region(universe, &rect))
+}
+
+pub fn app_fill_region(rect: Cam) -> Result<(), RegionTooLargeErr> {
+    edit_region(|universe| universe_fill_region(universe, &rect))
+}
+
+pub fn app_fill_random_region(rect: Cam, density: f64, seed: u64) -> Result<(), RegionTooLargeErr> {
+    edit_region(|universe| {
+        universe_fill_random_region(universe, &rect, density, &mut random_from_seed(seed))
+    })
+}
+
 pub fn app_zoom_in() {
     let cam = MODEL.with(|m| m.borrow().settings.render_settings.cam.clone());
     if manfredo::cartesian::rect::rect_i32::max_len(&cam) <= 2 {
@@ -395,18 +744,138 @@ pub fn app_get_settings() -> AppInfo {
 mod tests {
     use libre_game_of_life_lib::{
         cell::State,
+        heat::HeatMetric,
+        pattern::{PatternFromStrErr, UnknownFormatErr},
         preset::get_preset,
         render_settings::{Cam, RenderSettings},
-        universe::{CartesianPoint, Universe},
+        rle::{InvalidCharacterErr, RleFromStrErr},
+        rule::{InvalidFormatErr, Rule, RuleFromStrErr, rule_from_str},
+        soup::{NonSquareBoundsErr, SOUP_PRESET_ID, Soup, Symmetry, soup_generate},
+        table::{InvalidHeaderErr, TableFromStrErr},
+        topology::{Bounds, Topology},
+        universe::{CartesianPoint, PasteMode, RegionTooLargeErr, Universe, universe_iterate},
     };
     use std::collections::HashMap;
 
     use super::{
-        AppInfo, AppSettings, MODEL, Status, app_get_settings, app_iterate, app_move_cam,
-        app_pause, app_resume, app_set_dimension, app_set_fps, app_set_gap, app_set_preset,
-        app_single_iteration, app_toggle_by_point, app_zoom_in, app_zoom_out, app_zoom_to,
+        ALIVE_COLOR, AppInfo, AppSettings, DEAD_COLOR, MODEL, Status, age_color, app_clear_region,
+        app_crop, app_fill_random_region, app_fill_region, app_get_settings, app_invert_region,
+        app_iterate, app_load_pattern, app_load_rle, app_move_cam, app_paste_preset_at_point,
+        app_pause, app_resume, app_save_rle, app_set_age_tracking, app_set_dimension, app_set_fps,
+        app_set_gap, app_set_heat_map, app_set_preset, app_set_rule, app_set_rule_table,
+        app_set_soup, app_set_topology, app_single_iteration, app_step_back, app_toggle_by_point,
+        app_zoom_in, app_zoom_out, app_zoom_to, build_preset_option_groups, heat_color,
+        state_color,
     };
 
+    #[test]
+    fn test_state_color() {
+        assert_eq!(state_color(&State::Dead, 3), DEAD_COLOR);
+        assert_eq!(state_color(&State::Alive, 3), ALIVE_COLOR);
+        assert_eq!(state_color(&State::Dying(2), 3), "#676767");
+        assert_eq!(state_color(&State::Dying(2), 4), "#595959");
+        assert_eq!(state_color(&State::Dying(3), 4), "#848484");
+        assert_eq!(state_color(&State::Other(2), 2), "#1d6fd8");
+        assert_eq!(state_color(&State::Other(3), 2), "#e63946");
+        assert_eq!(state_color(&State::Other(8), 2), "#1d6fd8");
+    }
+
+    #[test]
+    fn test_age_color() {
+        assert_eq!(age_color(0), "#f5a623");
+        assert_eq!(age_color(32), "#916a28");
+        assert_eq!(age_color(64), ALIVE_COLOR);
+        assert_eq!(age_color(1000), ALIVE_COLOR);
+    }
+
+    #[test]
+    fn test_heat_color() {
+        assert_eq!(heat_color(0.0), "rgba(230, 57, 70, 0.00)");
+        assert_eq!(heat_color(0.5), "rgba(230, 57, 70, 0.40)");
+        assert_eq!(heat_color(1.0), "rgba(230, 57, 70, 0.80)");
+        assert_eq!(heat_color(2.0), "rgba(230, 57, 70, 0.80)");
+    }
+
+    #[test]
+    fn test_heat_map() {
+        app_set_preset("blinker".into());
+        app_set_heat_map(true, HeatMetric::Changes, 1.0);
+        assert_eq!(MODEL.with(|m| m.borrow().heat_metric), HeatMetric::Changes);
+        app_single_iteration();
+        app_single_iteration();
+        let heat = MODEL.with(|m| m.borrow().heat.clone()).unwrap();
+        assert_eq!(heat.generations, 2);
+        assert_eq!(heat.cells.len(), 5);
+        app_set_heat_map(true, HeatMetric::Alive, 2.0);
+        let heat = MODEL.with(|m| m.borrow().heat.clone()).unwrap();
+        assert_eq!(heat.generations, 2);
+        assert_eq!(heat.decay, 1.0);
+        app_set_preset("block".into());
+        assert_eq!(MODEL.with(|m| m.borrow().heat.clone().unwrap().generations), 0);
+        app_set_heat_map(false, HeatMetric::Alive, 1.0);
+        assert_eq!(MODEL.with(|m| m.borrow().heat.clone()), None);
+    }
+
+    #[test]
+    fn test_rle() {
+        assert_eq!(app_load_rle("x = 3, y = 3, rule = B36/S23\nobo$b2o$bo!".into()), Ok(()));
+        MODEL.with(|m| {
+            let model = m.borrow();
+            assert_eq!(model.universe, get_preset("glider"));
+            assert_eq!(model.rule, rule_from_str("B36/S23"));
+            assert_eq!(model.settings.preset, None);
+        });
+        assert_eq!(app_save_rle(), "#R -1 -1\nx = 3, y = 3, rule = B36/S23\nobo$b2o$bo!\n");
+        assert_eq!(
+            app_load_rle("x = 1, y = 1\nz!".into()),
+            Err(RleFromStrErr::InvalidCharacter(InvalidCharacterErr {
+                line: 2,
+                column: 1,
+                character: 'z'
+            }))
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), get_preset("glider"));
+    }
+
+    #[test]
+    fn test_load_pattern() {
+        assert_eq!(app_set_rule("B36/S23".into()), Ok(()));
+        assert_eq!(app_load_pattern("#Life 1.06\n0 0\n1 0\n0 1\n1 1\n".into()), Ok(()));
+        assert_eq!(
+            MODEL.with(|m| m.borrow().universe.clone()),
+            Universe::from([
+                CartesianPoint::of(0, 0),
+                CartesianPoint::of(1, 0),
+                CartesianPoint::of(0, -1),
+                CartesianPoint::of(1, -1),
+            ])
+        );
+        assert_eq!(app_load_pattern("!Glider\nO.O\n.OO\n.O\n".into()), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), get_preset("glider"));
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), rule_from_str("B36/S23"));
+        assert_eq!(app_load_pattern("x = 2, y = 2, rule = B3/S23\n2o$2o!".into()), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), Rule::default());
+        assert_eq!(
+            app_load_pattern("hello".into()),
+            Err(PatternFromStrErr::UnknownFormat(UnknownFormatErr))
+        );
+    }
+
+    #[test]
+    fn test_age_tracking() {
+        app_set_age_tracking(true);
+        assert!(MODEL.with(|m| m.borrow().age_tracking));
+        app_single_iteration();
+        app_single_iteration();
+        let ages = MODEL.with(|m| m.borrow().universe.ages.clone()).unwrap();
+        assert_eq!(ages.len(), 4);
+        assert!(ages.values().all(|age| *age == 2));
+        app_set_age_tracking(false);
+        assert_eq!(MODEL.with(|m| m.borrow().universe.ages.clone()), None);
+        app_single_iteration();
+        assert_eq!(MODEL.with(|m| m.borrow().universe.ages.clone()), None);
+    }
+
     #[test]
     fn test_instance() {
         assert_eq!(
@@ -419,6 +888,7 @@ mod tests {
             }
         );
         assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), get_preset("block"));
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), Rule::default());
         let settings = app_get_settings();
         assert_eq!(
             AppInfo {
@@ -512,7 +982,7 @@ mod tests {
         let block = get_preset("block");
         assert_eq!(
             MODEL.with(|m| m.borrow().universe.clone()),
-            Universe { age: 1, value: block.value.clone() }
+            Universe { age: 1, value: block.value.clone(), ..Default::default() }
         );
         assert_eq!(
             MODEL.with(|m| m.borrow().settings.clone()),
@@ -527,7 +997,7 @@ mod tests {
         app_single_iteration();
         assert_eq!(
             MODEL.with(|m| m.borrow().universe.clone()),
-            Universe { age: 2, value: block.value.clone() }
+            Universe { age: 2, value: block.value.clone(), ..Default::default() }
         );
         assert_eq!(
             MODEL.with(|m| m.borrow().settings.clone()),
@@ -618,7 +1088,7 @@ mod tests {
         app_move_cam(CartesianPoint::of(20, 20));
         assert_eq!(
             MODEL.with(|m| m.borrow().universe.clone()),
-            Universe { age: 2, value: block.value.clone() }
+            Universe { age: 2, value: block.value.clone(), ..Default::default() }
         );
 
         app_toggle_by_point(CartesianPoint::of(0, 0));
@@ -630,8 +1100,185 @@ mod tests {
                     (CartesianPoint::of(-1, -1), State::Alive),
                     (CartesianPoint::of(-1, 0), State::Alive),
                     (CartesianPoint::of(0, -1), State::Alive),
-                ])
+                ]),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            app_set_rule("B3/S2x".into()),
+            Err(RuleFromStrErr::InvalidFormat(InvalidFormatErr))
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), Rule::default());
+        assert_eq!(app_set_rule("B36/S23".into()), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), rule_from_str("B36/S23"));
+        assert_eq!(
+            app_set_rule_table("@RULE Test\n@TABLE\nn_states:2".into()),
+            Err(TableFromStrErr::InvalidHeader(InvalidHeaderErr))
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().rule.clone()), rule_from_str("B36/S23"));
+        assert_eq!(
+            app_set_rule_table(
+                "@RULE Test\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n0,1,0,0,0,2".into()
+            ),
+            Ok(())
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().rule.to_string()), "Test");
+        assert_eq!(MODEL.with(|m| m.borrow().rule.generations), 3);
+        assert_eq!(app_set_rule("B36/S23".into()), Ok(()));
+
+        app_set_topology(Topology::Bounded(Bounds::of(-1, -1, -1, 0)));
+        assert_eq!(
+            MODEL.with(|m| m.borrow().universe.clone()),
+            Universe {
+                age: 2,
+                value: HashMap::from([
+                    (CartesianPoint::of(-1, -1), State::Alive),
+                    (CartesianPoint::of(-1, 0), State::Alive),
+                ]),
+                topology: Topology::Bounded(Bounds::of(-1, -1, -1, 0)),
+                ..Default::default()
             }
         );
+        app_set_preset("blinker".into());
+        assert_eq!(
+            MODEL.with(|m| m.borrow().universe.topology.clone()),
+            Topology::Bounded(Bounds::of(-1, -1, -1, 0))
+        );
+    }
+
+    #[test]
+    fn test_step_back() {
+        app_set_preset("blinker".into());
+        let blinker = get_preset("blinker");
+        app_step_back();
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), blinker);
+
+        app_single_iteration();
+        app_single_iteration();
+        assert_eq!(
+            MODEL.with(|m| m.borrow().universe.clone()),
+            Universe { age: 2, ..blinker.clone() }
+        );
+
+        app_step_back();
+        let universe = MODEL.with(|m| m.borrow().universe.clone());
+        assert_eq!(universe.age, 1);
+        assert_ne!(universe.value, blinker.value);
+
+        app_step_back();
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), blinker);
+        app_step_back();
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), blinker);
+    }
+
+    #[test]
+    fn test_step_back_ages() {
+        app_set_preset("gosper_glider_gun".into());
+        app_set_age_tracking(true);
+        for _ in 0..5 {
+            app_single_iteration();
+        }
+        let previous = MODEL.with(|m| m.borrow().universe.clone());
+        assert!(previous.ages.as_ref().is_some_and(|ages| ages.values().any(|age| *age > 0)));
+        app_single_iteration();
+        app_step_back();
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), previous);
+        app_set_age_tracking(false);
+    }
+
+    #[test]
+    fn test_paste_preset() {
+        app_set_preset("block".into());
+        assert_eq!(
+            app_paste_preset_at_point("blinker".into(), CartesianPoint::of(10, 0), PasteMode::Or),
+            Ok(())
+        );
+        let universe = MODEL.with(|m| m.borrow().universe.clone());
+        assert_eq!(universe.value.len(), 7);
+        assert_eq!(universe.value.get(&CartesianPoint::of(10, 1)), Some(&State::Alive));
+        assert_eq!(app_get_settings().preset, None);
+
+        assert_eq!(
+            app_paste_preset_at_point("blinker".into(), CartesianPoint::of(10, 0), PasteMode::Xor),
+            Ok(())
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.clone()), get_preset("block").value);
+
+        assert_eq!(
+            app_paste_preset_at_point("unknown".into(), CartesianPoint::of(0, 0), PasteMode::Copy),
+            Ok(())
+        );
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.clone()), get_preset("block").value);
+    }
+
+    #[test]
+    fn test_region() {
+        app_set_preset("block".into());
+        assert_eq!(app_fill_region(Cam::of(-1, -1, 1, 1)), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.len()), 9);
+        assert_eq!(app_get_settings().preset, None);
+        assert_eq!(app_invert_region(Cam::of(-1, -1, 1, 1)), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.len()), 0);
+        assert_eq!(app_fill_random_region(Cam::of(-10, -10, 9, 9), 0.5, 1), Ok(()));
+        let universe = MODEL.with(|m| m.borrow().universe.clone());
+        assert!(universe.value.len() > 100 && universe.value.len() < 300);
+        assert_eq!(app_crop(Cam::of(0, 0, 9, 9)), Ok(()));
+        let cropped = MODEL.with(|m| m.borrow().universe.clone());
+        assert!(cropped.value.keys().all(|p| p.x >= 0 && p.y >= 0));
+        assert_eq!(app_clear_region(Cam::of(0, 0, 9, 9)), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.len()), 0);
+    }
+
+    #[test]
+    fn test_tiles() {
+        app_set_preset("gosper_glider_gun".into());
+        let mut expected = get_preset("gosper_glider_gun");
+        for _ in 0..31 {
+            app_single_iteration();
+            universe_iterate(&mut expected, &Rule::default());
+            assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), expected);
+        }
+        assert_eq!(MODEL.with(|m| m.borrow().tiles.as_ref().map(|tiles| tiles.age)), Some(31));
+        app_step_back();
+        assert!(MODEL.with(|m| m.borrow().tiles.is_none()));
+        assert_eq!(app_get_settings().age, 30);
+    }
+
+    #[test]
+    fn test_region_too_large() {
+        app_set_preset("block".into());
+        let rect = Cam::of(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+        assert_eq!(app_fill_region(rect.clone()), Err(RegionTooLargeErr));
+        assert_eq!(app_get_settings().preset, Some("block".into()));
+        assert_eq!(app_clear_region(rect), Ok(()));
+        assert_eq!(MODEL.with(|m| m.borrow().universe.value.len()), 0);
+    }
+
+    #[test]
+    fn test_random_preset() {
+        let soup = Soup { seed: 99, symmetry: Symmetry::C2, ..Default::default() };
+        assert_eq!(app_set_soup(soup.clone()), Ok(()));
+        app_set_preset(SOUP_PRESET_ID.into());
+        assert_eq!(MODEL.with(|m| m.borrow().universe.clone()), soup_generate(&soup).unwrap());
+        let oblong =
+            Soup { bounds: Bounds::of(0, 0, 7, 3), symmetry: Symmetry::D8, ..soup.clone() };
+        assert_eq!(app_set_soup(oblong), Err(NonSquareBoundsErr));
+        assert_eq!(MODEL.with(|m| m.borrow().soup.clone()), soup);
+        assert_eq!(app_get_settings().preset, Some(SOUP_PRESET_ID.into()));
+        assert!(build_preset_option_groups().iter().any(|group| group.value == SOUP_PRESET_ID));
+    }
+
+    #[test]
+    fn test_empty_preset() {
+        app_set_preset("block".into());
+        let cam = MODEL.with(|m| m.borrow().settings.render_settings.cam.clone());
+        assert_eq!(app_set_soup(Soup { density: 0.0, ..Default::default() }), Ok(()));
+        app_set_preset(SOUP_PRESET_ID.into());
+        MODEL.with(|m| {
+            let model = m.borrow();
+            assert_eq!(model.universe.value.len(), 0);
+            assert_eq!(model.settings.render_settings.cam, cam);
+        });
     }
 }
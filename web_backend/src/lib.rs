@@ -1,13 +1,21 @@
 pub mod app;
 
 use crate::app::{
-    Status, add_on_change_listener, app_get_settings, app_init, app_move_cam, app_pause,
-    app_resume, app_set_dimension, app_set_fps, app_set_gap, app_set_preset, app_single_iteration,
-    app_toggle_model_cell_by_absolute_point, app_zoom_in, app_zoom_out, app_zoom_to,
+    Status, add_on_change_listener, app_clear_region, app_crop, app_fill_random_region,
+    app_fill_region, app_get_settings, app_init, app_invert_region, app_load_pattern, app_load_rle,
+    app_move_cam, app_paste_preset_by_absolute_point, app_pause, app_region_by_absolute_points,
+    app_resume, app_save_rle, app_set_age_tracking, app_set_dimension, app_set_fps, app_set_gap,
+    app_set_heat_map, app_set_preset, app_set_rule, app_set_rule_table, app_set_soup,
+    app_set_topology, app_single_iteration, app_step_back, app_toggle_model_cell_by_absolute_point,
+    app_zoom_in, app_zoom_out, app_zoom_to,
 };
 use libre_game_of_life_lib::{
+    heat::HeatMetric,
     preset::get_preset_groups,
-    universe::{CartesianPoint, MatrixPoint},
+    render_settings::Cam,
+    soup::{SOUP_PRESET_ID, Soup, Symmetry},
+    topology::{Bounds, Topology},
+    universe::{CartesianPoint, MatrixPoint, PasteMode},
 };
 
 use js_sys::Function;
@@ -52,6 +60,43 @@ pub enum EngineStatus {
     Paused,
 }
 
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum EngineTopology {
+    Plane,
+    Bounded,
+    Torus,
+    KleinBottle,
+    CrossSurface,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum EngineHeatMetric {
+    Alive,
+    Changes,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum EnginePasteMode {
+    Or,
+    Xor,
+    And,
+    AndNot,
+    Copy,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum EngineSymmetry {
+    C1,
+    C2,
+    C4,
+    D4,
+    D8,
+}
+
 #[wasm_bindgen]
 pub struct EngineInfo {
     preset: Option<String>,
@@ -87,6 +132,13 @@ pub fn main_init(value: CanvasRenderingContext2d) {
     app_init(value);
 }
 
+fn region(a: EngineMatrixPoint, b: EngineMatrixPoint) -> Cam {
+    app_region_by_absolute_points(
+        MatrixPoint { row: a.row, col: a.col },
+        MatrixPoint { row: b.row, col: b.col },
+    )
+}
+
 #[wasm_bindgen(js_name = "enginePause")]
 pub fn main_pause() {
     app_pause();
@@ -117,16 +169,158 @@ pub fn main_set_preset(preset: String) {
     app_set_preset(preset);
 }
 
+#[wasm_bindgen(js_name = "engineSetSoup")]
+pub fn main_set_soup(
+    width: u16,
+    height: u16,
+    density: f64,
+    seed: u64,
+    symmetry: EngineSymmetry,
+) -> Result<(), String> {
+    let width = i32::from(width.max(1));
+    let height = i32::from(height.max(1));
+    app_set_soup(Soup {
+        bounds: Bounds::of(
+            -width / 2,
+            -height / 2,
+            -width / 2 + width - 1,
+            -height / 2 + height - 1,
+        ),
+        density,
+        seed,
+        symmetry: match symmetry {
+            EngineSymmetry::C1 => Symmetry::C1,
+            EngineSymmetry::C2 => Symmetry::C2,
+            EngineSymmetry::C4 => Symmetry::C4,
+            EngineSymmetry::D4 => Symmetry::D4,
+            EngineSymmetry::D8 => Symmetry::D8,
+        },
+    })
+    .map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineSetRule")]
+pub fn main_set_rule(rule: String) -> Result<(), String> {
+    app_set_rule(rule).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineSetRuleTable")]
+pub fn main_set_rule_table(table: String) -> Result<(), String> {
+    app_set_rule_table(table).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineLoadRle")]
+pub fn main_load_rle(rle: String) -> Result<(), String> {
+    app_load_rle(rle).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineLoadPattern")]
+pub fn main_load_pattern(pattern: String) -> Result<(), String> {
+    app_load_pattern(pattern).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineSaveRle")]
+pub fn main_save_rle() -> String {
+    app_save_rle()
+}
+
+#[wasm_bindgen(js_name = "engineSetAgeTracking")]
+pub fn main_set_age_tracking(enabled: bool) {
+    app_set_age_tracking(enabled);
+}
+
+#[wasm_bindgen(js_name = "engineSetHeatMap")]
+pub fn main_set_heat_map(enabled: bool, metric: EngineHeatMetric, decay: f64) {
+    app_set_heat_map(
+        enabled,
+        match metric {
+            EngineHeatMetric::Alive => HeatMetric::Alive,
+            EngineHeatMetric::Changes => HeatMetric::Changes,
+        },
+        decay,
+    );
+}
+
+#[wasm_bindgen(js_name = "engineSetTopology")]
+pub fn main_set_topology(topology: EngineTopology, width: u16, height: u16) {
+    let width = i32::from(width.max(1));
+    let height = i32::from(height.max(1));
+    let bounds =
+        Bounds::of(-width / 2, -height / 2, -width / 2 + width - 1, -height / 2 + height - 1);
+    app_set_topology(match topology {
+        EngineTopology::Plane => Topology::Plane,
+        EngineTopology::Bounded => Topology::Bounded(bounds),
+        EngineTopology::Torus => Topology::Torus(bounds),
+        EngineTopology::KleinBottle => Topology::KleinBottle(bounds),
+        EngineTopology::CrossSurface => Topology::CrossSurface(bounds),
+    });
+}
+
 #[wasm_bindgen(js_name = "engineSingleIteration")]
 pub fn main_single_iteration() {
     app_single_iteration();
 }
 
+#[wasm_bindgen(js_name = "engineStepBack")]
+pub fn main_step_back() {
+    app_step_back();
+}
+
 #[wasm_bindgen(js_name = "engineToggle")]
 pub fn main_toggle(point: EngineMatrixPoint) {
     app_toggle_model_cell_by_absolute_point(MatrixPoint { row: point.row, col: point.col });
 }
 
+#[wasm_bindgen(js_name = "enginePastePreset")]
+pub fn main_paste_preset(
+    preset: String,
+    point: EngineMatrixPoint,
+    mode: EnginePasteMode,
+) -> Result<(), String> {
+    app_paste_preset_by_absolute_point(
+        preset,
+        MatrixPoint { row: point.row, col: point.col },
+        match mode {
+            EnginePasteMode::Or => PasteMode::Or,
+            EnginePasteMode::Xor => PasteMode::Xor,
+            EnginePasteMode::And => PasteMode::And,
+            EnginePasteMode::AndNot => PasteMode::AndNot,
+            EnginePasteMode::Copy => PasteMode::Copy,
+        },
+    )
+    .map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineClearRegion")]
+pub fn main_clear_region(a: EngineMatrixPoint, b: EngineMatrixPoint) -> Result<(), String> {
+    app_clear_region(region(a, b)).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineCrop")]
+pub fn main_crop(a: EngineMatrixPoint, b: EngineMatrixPoint) -> Result<(), String> {
+    app_crop(region(a, b)).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineInvertRegion")]
+pub fn main_invert_region(a: EngineMatrixPoint, b: EngineMatrixPoint) -> Result<(), String> {
+    app_invert_region(region(a, b)).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineFillRegion")]
+pub fn main_fill_region(a: EngineMatrixPoint, b: EngineMatrixPoint) -> Result<(), String> {
+    app_fill_region(region(a, b)).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen(js_name = "engineFillRandomRegion")]
+pub fn main_fill_random_region(
+    a: EngineMatrixPoint,
+    b: EngineMatrixPoint,
+    density: f64,
+    seed: u64,
+) -> Result<(), String> {
+    app_fill_random_region(region(a, b), density, seed).map_err(|err| err.to_string())
+}
+
 #[wasm_bindgen(js_name = "engineZoomIn")]
 pub fn main_zoom_in() {
     app_zoom_in();
@@ -194,6 +388,10 @@ pub fn main_get_presets() -> JsValue {
                 })
                 .collect(),
         })
+        .chain([EnginePresetGroup {
+            info: EnginePresetInfo { id: SOUP_PRESET_ID.into(), name: "Random".into() },
+            items: vec![EnginePresetInfo { id: SOUP_PRESET_ID.into(), name: "Random soup".into() }],
+        }])
         .collect();
     serde_wasm_bindgen::to_value(&groups).unwrap()
 }